@@ -0,0 +1,285 @@
+#!/usr/bin/env python3
+"""
+Batch Processor
+Processes a list of web article and YouTube URLs into blog posts, keeping
+per-URL state in a JSON file so an interrupted batch can be resumed.
+"""
+
+import argparse
+import asyncio
+import csv
+import json
+import logging
+import os
+import sys
+import time
+from pathlib import Path
+from typing import Any, Dict, List, Optional
+
+from dotenv import load_dotenv
+
+# Import our modular processors
+sys.path.append(str(Path(__file__).parent.parent))
+
+from processors.youtube_processor import validate_youtube_url, is_youtube_collection_url
+from processors.tts_engine import TTS_BACKENDS
+from processors.cache import add_cache_arguments, configure_cache
+from core.web_to_blog import process_web_article
+from core.youtube_to_blog import process_youtube_video, process_youtube_collection, parse_date_option
+
+# Setup logging
+logging.basicConfig(
+    level=logging.INFO,
+    format='%(asctime)s - %(levelname)s - %(message)s'
+)
+logger = logging.getLogger(__name__)
+
+# Load environment variables
+load_dotenv()
+
+# Constants
+DEFAULT_STATE_FILE = Path("batch_progress.json")
+
+PENDING, RUNNING, DONE, FAILED = "pending", "running", "done", "failed"
+
+
+class LastErrorHandler(logging.Handler):
+    """Remembers the last error logged while a job runs (the pipelines log instead of raising)."""
+
+    def __init__(self):
+        super().__init__(level=logging.ERROR)
+        self.message = None
+
+    def emit(self, record: logging.LogRecord) -> None:
+        self.message = record.getMessage()
+
+
+def load_jobs(input_file: Path) -> List[Dict[str, Any]]:
+    """
+    Read jobs from a text, CSV or JSON file.
+
+    - ``.txt``: one URL per line; blank lines and ``#`` comments are ignored
+    - ``.csv``: a ``url`` column, plus optional ``tts_backend``, ``voice``
+      and ``podcast`` columns, and ``after``, ``before`` (YYYY-MM-DD) and
+      ``max`` for playlists and channels
+    - ``.json``: a list of URLs or of objects with the same keys as the CSV
+
+    Args:
+        input_file: Path to the job list
+
+    Returns:
+        List of job dictionaries with at least a ``url`` key
+    """
+    suffix = input_file.suffix.lower()
+    with open(input_file, "r", encoding="utf-8") as f:
+        if suffix == ".json":
+            entries = json.load(f)
+            jobs = [{"url": entry} if isinstance(entry, str) else dict(entry) for entry in entries]
+        elif suffix == ".csv":
+            jobs = [{k.strip(): (v or "").strip() for k, v in row.items() if k} for row in csv.DictReader(f)]
+        else:
+            jobs = [{"url": line.strip()} for line in f if line.strip() and not line.strip().startswith("#")]
+
+    valid = []
+    for job in jobs:
+        url = str(job.get("url", "")).strip()
+        if not url.startswith(("http://", "https://")):
+            logger.warning(f"⚠️ Skipping invalid URL: {url!r}")
+            continue
+        job["url"] = url
+        if isinstance(job.get("podcast"), str):
+            job["podcast"] = job["podcast"].lower() in ("1", "true", "yes")
+        try:
+            for key in ("after", "before"):
+                if job.get(key):
+                    job[key] = parse_date_option(str(job[key]))
+            if job.get("max") not in ("", None):
+                job["max"] = int(job["max"])
+        except (argparse.ArgumentTypeError, ValueError) as e:
+            logger.warning(f"⚠️ Skipping {url}: {e}")
+            continue
+        valid.append(job)
+    return valid
+
+
+def load_state(state_file: Path) -> Dict[str, Any]:
+    """Load batch state (``{"jobs": {url: {...}}}``) from the state file."""
+    try:
+        with open(state_file, "r", encoding="utf-8") as f:
+            return json.load(f)
+    except (FileNotFoundError, json.JSONDecodeError):
+        return {"jobs": {}}
+
+
+def save_state(state: Dict[str, Any], state_file: Path) -> None:
+    """Write batch state atomically so a crash never leaves a truncated file."""
+    temp_file = state_file.with_suffix(".tmp")
+    with open(temp_file, "w", encoding="utf-8") as f:
+        json.dump(state, f, indent=2, ensure_ascii=False)
+    os.replace(temp_file, state_file)
+
+
+def merge_jobs(state: Dict[str, Any], jobs: List[Dict[str, Any]], retry_failed: bool = False) -> None:
+    """
+    Add new jobs to the state and prepare existing ones for this run.
+
+    Jobs left ``running`` by a crashed run go back to ``pending``; failed jobs
+    are retried only with ``retry_failed``. Jobs of other input files are not touched.
+    """
+    records = state.setdefault("jobs", {})
+    for job in jobs:
+        record = records.setdefault(job["url"], {"status": PENDING, "attempts": 0, "error": None})
+        if is_youtube_collection_url(job["url"]):
+            record["kind"] = "collection"
+        else:
+            record["kind"] = "youtube" if validate_youtube_url(job["url"]) else "web"
+        record["options"] = {k: v for k, v in job.items() if k != "url" and v not in ("", None)}
+
+    for url in dict.fromkeys(job["url"] for job in jobs):
+        record = records[url]
+        if record["status"] == RUNNING:
+            logger.info(f"🔁 Resuming interrupted job: {url}")
+            record["status"] = PENDING
+        elif record["status"] == FAILED and retry_failed:
+            record["status"] = PENDING
+
+
+async def run_job(url: str, record: Dict[str, Any], tts_backend: Optional[str], voice: Optional[str]) -> bool:
+    """
+    Dispatch one URL to the web, YouTube or playlist/channel pipeline.
+
+    Args:
+        url: URL to process
+        record: Job state record (``kind`` and per-job ``options``)
+        tts_backend: Default TTS backend (settings default if None)
+        voice: Default voice
+
+    Returns:
+        True if the pipeline succeeded
+    """
+    options = record.get("options", {})
+    backend = options.get("tts_backend", tts_backend)
+    job_voice = options.get("voice", voice)
+
+    if record["kind"] == "collection":
+        return await process_youtube_collection(url, backend, job_voice, bool(options.get("podcast", False)),
+                                                options.get("after"), options.get("before"), options.get("max"))
+    if record["kind"] == "youtube":
+        return await process_youtube_video(url, backend, job_voice, bool(options.get("podcast", False)))
+    return await process_web_article(url, backend, job_voice)
+
+
+async def process_batch(state: Dict[str, Any], state_file: Path, urls: List[str], tts_backend: Optional[str] = None,
+                        voice: Optional[str] = None) -> None:
+    """
+    Process the pending jobs of this run's input file in order, saving state
+    after each transition.
+
+    Args:
+        state: Batch state
+        state_file: Path of the state file
+        urls: URLs from the current input file (other jobs in the state are left alone)
+        tts_backend: Default TTS backend
+        voice: Default voice
+    """
+    pending = [url for url in urls if state["jobs"][url]["status"] == PENDING]
+    logger.info(f"📋 {len(pending)} pending job(s) out of {len(urls)}")
+
+    for index, url in enumerate(pending, start=1):
+        record = state["jobs"][url]
+        logger.info(f"🚀 [{index}/{len(pending)}] {record['kind']}: {url}")
+
+        record.update({"status": RUNNING, "started_at": time.time(), "error": None})
+        record["attempts"] = record.get("attempts", 0) + 1
+        save_state(state, state_file)
+
+        error_handler = LastErrorHandler()
+        logging.getLogger().addHandler(error_handler)
+        try:
+            success = await run_job(url, record, tts_backend, voice)
+            error = None if success else (error_handler.message or "Processing failed")
+        except Exception as e:
+            logger.exception(f"❌ Job crashed: {url}")
+            success, error = False, str(e)
+        finally:
+            logging.getLogger().removeHandler(error_handler)
+
+        record.update({
+            "status": DONE if success else FAILED,
+            "error": error,
+            "finished_at": time.time(),
+            "seconds": round(time.time() - record["started_at"], 1),
+        })
+        save_state(state, state_file)
+
+
+def print_summary(state: Dict[str, Any], urls: List[str]) -> int:
+    """
+    Log a summary of the jobs from the current input file.
+
+    Returns:
+        Number of failed jobs
+    """
+    records = {url: state["jobs"][url] for url in urls}
+    counts = {status: sum(1 for r in records.values() if r["status"] == status)
+              for status in (DONE, FAILED, PENDING)}
+
+    logger.info("📊 Batch summary")
+    logger.info(f"   ✅ Done:    {counts[DONE]}")
+    logger.info(f"   ❌ Failed:  {counts[FAILED]}")
+    logger.info(f"   ⏳ Pending: {counts[PENDING]}")
+
+    for url, record in records.items():
+        if record["status"] == FAILED:
+            logger.info(f"   - {url} (attempts: {record.get('attempts', 0)}): {record.get('error')}")
+    return counts[FAILED]
+
+
+def main():
+    """Main entry point for batch processing."""
+    parser = argparse.ArgumentParser(description="Process a list of web and YouTube URLs into blog posts")
+    parser.add_argument("input", type=Path, help="Job list: .txt (one URL per line), .csv (url column) or .json")
+    parser.add_argument("--state", type=Path, default=DEFAULT_STATE_FILE,
+                        help=f"JSON file with per-URL progress (default: {DEFAULT_STATE_FILE})")
+    parser.add_argument("--reset", "-r", action="store_true", help="Discard saved progress and start fresh")
+    parser.add_argument("--retry-failed", action="store_true", help="Run previously failed jobs again")
+    parser.add_argument("--tts-backend", choices=sorted(TTS_BACKENDS),
+                        help="Default TTS backend for narration (default: zolamac.toml, TTS_BACKEND env or 'edge')")
+    parser.add_argument("--voice", help="Default voice for the selected TTS backend")
+    add_cache_arguments(parser)
+    args = parser.parse_args()
+    configure_cache(args)
+
+    if not args.input.exists():
+        logger.error(f"❌ Input file not found: {args.input}")
+        sys.exit(1)
+
+    if args.reset and args.state.exists():
+        args.state.unlink()
+        logger.info("Progress reset. Starting from the beginning.")
+
+    jobs = load_jobs(args.input)
+    if not jobs:
+        logger.error("❌ No valid URLs in input file")
+        sys.exit(1)
+
+    # The state file is shared across input files; only this file's jobs are run and reported
+    urls = list(dict.fromkeys(job["url"] for job in jobs))
+    state = load_state(args.state)
+    merge_jobs(state, jobs, args.retry_failed)
+    save_state(state, args.state)
+
+    try:
+        asyncio.run(process_batch(state, args.state, urls, args.tts_backend, args.voice))
+    except KeyboardInterrupt:
+        logger.warning("⏸️ Interrupted. Run the same command again to resume.")
+
+    failures = print_summary(state, urls)
+    if failures:
+        logger.error(f"💥 {failures} job(s) failed. Use --retry-failed to run them again.")
+        sys.exit(1)
+    logger.info("🎉 Batch completed!")
+
+
+if __name__ == "__main__":
+    main()
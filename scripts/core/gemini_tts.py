@@ -4,38 +4,43 @@
 import argparse
 import base64
 import logging
-import mimetypes
 import os
 import re
 import struct
 import subprocess
 import sys
-import time
 import json
+from pathlib import Path
 from dotenv import load_dotenv
 from google import genai
 from google.genai import types
 
-load_dotenv()
+sys.path.append(str(Path(__file__).parent.parent))
 
-# Set up logging
-logging.basicConfig(
-    filename='gemini_tts.log',
-    level=logging.INFO,
-    format='%(asctime)s - %(levelname)s - %(message)s',
-    datefmt='%Y-%m-%d %H:%M:%S'
+from processors.tts_engine import (
+    split_text_into_chunks, convert_audio, master_audio, MASTERING_ENABLED, GEMINI_MAX_CHARS, CHUNK_PAUSE_SECONDS
 )
+from processors.key_pool import APIKeyPool, fingerprint
+
+load_dotenv()
 
-# Load and rotate API keys
-keys = [os.environ.get(f"GEMINI_API_KEY_{i+1}") for i in range(3)]
-key_index = int(time.time() // 60) % 3  # Rotate every minute
-api_key = keys[key_index]
+# Pool of GEMINI_API_KEY / GEMINI_API_KEY_<n> keys, picked by health instead of rotation
+key_pool = APIKeyPool.from_env("gemini", "GEMINI_API_KEY")
 
 # Load speech configuration parameters
 speech_rate = float(os.environ.get("SPEECH_RATE", "1.0"))
 pitch = float(os.environ.get("PITCH", "0.0"))
 volume_gain_db = float(os.environ.get("VOLUME_GAIN_DB", "0.0"))
 
+# Long text is split into pieces under the model's input limit (GEMINI_MAX_CHARS),
+# stitched with CHUNK_PAUSE_SECONDS (AUDIO_CHUNK_PAUSE) of silence like other backends
+
+# Multi-speaker dialogue mode
+MAX_DIALOGUE_SPEAKERS = 2
+DEFAULT_DIALOGUE_VOICES = ["Puck", "Kore"]
+DIALOGUE_LINE_PATTERN = re.compile(r"^([^\W\d_][\w .'-]{0,30})[:：]\s*(.+)$")
+DIALOGUE_PROMPT = "TTS the following conversation as a natural, friendly podcast:"
+
 # Load voice selection
 voices_str = os.environ.get("VOICES", "Algieba")
 voices = [voice.strip() for voice in voices_str.split(",")]
@@ -121,81 +126,378 @@ def apply_ffmpeg_effects(input_file):
             os.remove(temp_file)
 
 
-def generate(voice_name):
-    logging.info(f"Starting generation for voice: {voice_name}")
+def build_speech_config(voice_name):
+    """Build the generation config for a single prebuilt voice."""
+    return types.GenerateContentConfig(
+        temperature=1,
+        response_modalities=[
+            "audio",
+        ],
+        speech_config=types.SpeechConfig(
+            voice_config=types.VoiceConfig(
+                prebuilt_voice_config=types.PrebuiltVoiceConfig(
+                    voice_name=voice_name
+                )
+            )
+        ),
+    )
+
+
+def stream_audio(api_key, text, generate_content_config):
+    """Yield (data, mime_type) for every audio part streamed back by Gemini."""
+    client = genai.Client(
+        api_key=api_key,
+    )
 
-    # Try each API key in sequence until one works
-    for attempt_key_index in range(3):
-        attempt_api_key = keys[attempt_key_index]
-        logging.info(f"Trying API key index {attempt_key_index} (GEMINI_API_KEY_{attempt_key_index + 1}) for voice {voice_name}")
+    model = "gemini-2.5-flash-preview-tts"
+    contents = types.Content(
+        role="user",
+        parts=[
+            types.Part.from_text(text=text),
+        ],
+    )
+
+    for chunk in client.models.generate_content_stream(
+        model=model,
+        contents=contents,
+        config=generate_content_config,
+    ):
+        if (
+            chunk.candidates is None
+            or chunk.candidates[0].content is None
+            or chunk.candidates[0].content.parts is None
+        ):
+            continue
+        if chunk.candidates[0].content.parts[0].inline_data and chunk.candidates[0].content.parts[0].inline_data.data:
+            inline_data = chunk.candidates[0].content.parts[0].inline_data
+            if inline_data.data is None or inline_data.mime_type is None:
+                continue
+            yield inline_data.data, inline_data.mime_type
+        else:
+            print(chunk.text)
+
+
+def synthesize_piece(text, voice_name, generate_content_config=None):
+    """Synthesize one request-sized piece of text, trying each API key in turn.
 
+    Args:
+        text: Text to synthesize
+        voice_name: Voice name (or a label such as "dialogue" for logging)
+        generate_content_config: Config to send; defaults to a single prebuilt voice
+
+    Returns:
+        Tuple of (raw PCM bytes, mime type)
+    """
+    if generate_content_config is None:
+        generate_content_config = build_speech_config(voice_name)
+
+    if not len(key_pool):
+        raise RuntimeError("No GEMINI_API_KEY or GEMINI_API_KEY_<n> variables are set")
+
+    # One attempt per key; the pool skips keys already tried and keys that are cooling down.
+    # Never wait for a cooling key: failing fast lets generate_audio_from_text fall back to another backend
+    tried = set()
+    last_error = None
+    for attempt in range(len(key_pool)):
+        attempt_api_key = key_pool.acquire(wait=False, exclude=tried)
+        if attempt_api_key is None:
+            break
+        tried.add(attempt_api_key)
+        key_id = fingerprint(attempt_api_key)
+        logging.info(f"Trying API key {key_id} (attempt {attempt + 1}/{len(key_pool)}) for voice {voice_name}")
         try:
-            with open("input.txt", "r", encoding="utf-8") as f:
-                text = f.read()
+            pcm_data = b""
+            mime_type = None
+            for data_buffer, mime_type in stream_audio(attempt_api_key, text, generate_content_config):
+                pcm_data += data_buffer
+            if not pcm_data:
+                raise RuntimeError("Gemini returned no audio data")
 
-            client = genai.Client(
-                api_key=attempt_api_key,
-            )
+            key_pool.report_success(attempt_api_key)
+            logging.info(f"Successfully generated audio for voice: {voice_name} using API key {key_id}")
+            return pcm_data, mime_type
 
-            model = "gemini-2.5-flash-preview-tts"
-            contents = types.Content(
-                role="user",
-                parts=[
-                    types.Part.from_text(text=text),
-                ],
-            )
-            generate_content_config = types.GenerateContentConfig(
-                temperature=1,
-                response_modalities=[
-                    "audio",
-                ],
-                speech_config=types.SpeechConfig(
-                    voice_config=types.VoiceConfig(
-                        prebuilt_voice_config=types.PrebuiltVoiceConfig(
-                            voice_name=voice_name
-                        )
+        except Exception as e:
+            key_pool.report_error(attempt_api_key, e)
+            logging.warning(f"Failed with API key {key_id} for voice {voice_name}: {str(e)}")
+            last_error = e
+
+    if last_error is None:
+        raise RuntimeError("All Gemini API keys are cooling down")
+    logging.error(f"All API keys failed for voice {voice_name}. Last error: {str(last_error)}")
+    raise last_error
+
+
+def synthesize_to_file(text, voice_name, output_file):
+    """Synthesize text of any length with one voice into a single WAV file.
+
+    Text longer than GEMINI_MAX_CHARS is split on sentence boundaries, each
+    piece is synthesized separately, and the raw PCM is stitched together with
+    a short pause before a single WAV header is written.
+    """
+    pieces = split_text_into_chunks(text, GEMINI_MAX_CHARS)
+    if not pieces:
+        raise ValueError("No text to convert to speech")
+
+    pcm_data = b""
+    mime_type = None
+    for index, piece in enumerate(pieces):
+        logging.info(f"Synthesizing piece {index + 1}/{len(pieces)} ({len(piece)} chars) for voice {voice_name}")
+        print(f"Synthesizing piece {index + 1}/{len(pieces)} for voice {voice_name}")
+        piece_data, mime_type = synthesize_piece(piece, voice_name)
+        if pcm_data:
+            pcm_data += pcm_silence(CHUNK_PAUSE_SECONDS, mime_type)
+        pcm_data += piece_data
+
+    save_binary_file(output_file, convert_to_wav(pcm_data, mime_type))
+    return output_file
+
+
+def build_dialogue_config(speaker_voices):
+    """Build a multi-speaker generation config mapping each speaker label to a prebuilt voice."""
+    return types.GenerateContentConfig(
+        temperature=1,
+        response_modalities=[
+            "audio",
+        ],
+        speech_config=types.SpeechConfig(
+            multi_speaker_voice_config=types.MultiSpeakerVoiceConfig(
+                speaker_voice_configs=[
+                    types.SpeakerVoiceConfig(
+                        speaker=speaker,
+                        voice_config=types.VoiceConfig(
+                            prebuilt_voice_config=types.PrebuiltVoiceConfig(
+                                voice_name=voice_name
+                            )
+                        ),
                     )
-                ),
+                    for speaker, voice_name in speaker_voices.items()
+                ]
             )
+        ),
+    )
 
-            file_index = 0
-            for chunk in client.models.generate_content_stream(
-                model=model,
-                contents=contents,
-                config=generate_content_config,
-            ):
-                if (
-                    chunk.candidates is None
-                    or chunk.candidates[0].content is None
-                    or chunk.candidates[0].content.parts is None
-                ):
-                    continue
-                if chunk.candidates[0].content.parts[0].inline_data and chunk.candidates[0].content.parts[0].inline_data.data:
-                    file_name = f"{voice_name}_{file_index}"
-                    file_index += 1
-                    inline_data = chunk.candidates[0].content.parts[0].inline_data
-                    data_buffer = inline_data.data
-                    mime_type = inline_data.mime_type
-                    if data_buffer is None or mime_type is None:
-                        continue
-                    file_extension = mimetypes.guess_extension(mime_type)
-                    if file_extension is None:
-                        file_extension = ".wav"
-                        data_buffer = convert_to_wav(data_buffer, mime_type)
-                    save_binary_file(f"{file_name}{file_extension}", data_buffer)
-                else:
-                    print(chunk.text)
-
-            logging.info(f"Successfully generated audio for voice: {voice_name} using API key index {attempt_key_index}")
-            return  # Success, exit the function
 
-        except Exception as e:
-            logging.warning(f"Failed with API key index {attempt_key_index} for voice {voice_name}: {str(e)}")
-            if attempt_key_index < 2:  # If not the last key, continue to next
-                continue
-            else:  # All keys failed
-                logging.error(f"All API keys failed for voice {voice_name}. Last error: {str(e)}")
-                raise
+def parse_dialogue(script, speakers=None):
+    """Parse a "Speaker: line" script into a list of (speaker, text) turns.
+
+    Only the given speaker labels, or labels introduced in the opening
+    turns (up to MAX_DIALOGUE_SPEAKERS), start a turn. Any other
+    line, including one that merely looks labelled ("Note: ...", "Step 1: ..."),
+    continues the previous speaker's turn. Unlabelled lines before the first
+    turn are logged and given to the first speaker.
+    """
+    speakers = list(speakers or [])
+    turns = []
+    preamble = []
+    for line in script.splitlines():
+        line = line.strip()
+        if not line:
+            continue
+        match = DIALOGUE_LINE_PATTERN.match(line)
+        label = match.group(1).strip() if match else None
+        if label and label not in speakers and len(speakers) < MAX_DIALOGUE_SPEAKERS \
+                and len(turns) < MAX_DIALOGUE_SPEAKERS:
+            speakers.append(label)
+        if label in speakers:
+            turns.append((label, match.group(2).strip()))
+        elif turns:
+            speaker, text = turns[-1]
+            turns[-1] = (speaker, f"{text} {line}")
+        else:
+            preamble.append(line)
+    if preamble and turns:
+        logging.warning(f"Dialogue script starts with {len(preamble)} unlabelled line(s); "
+                        f"giving them to {turns[0][0]}")
+        speaker, text = turns[0]
+        turns[0] = (speaker, " ".join(preamble + [text]))
+    return turns
+
+
+def split_dialogue(turns, max_chars):
+    """Group (speaker, text) turns into "Speaker: text" pieces of at most max_chars.
+
+    Pieces break on turn boundaries; a single turn longer than max_chars is
+    split on sentence boundaries into several turns by the same speaker.
+    """
+    lines = []
+    for speaker, text in turns:
+        label = f"{speaker}: "
+        for part in split_text_into_chunks(text, max(1, max_chars - len(label) - 1)):
+            lines.append(f"{label}{part}\n")
+
+    pieces = []
+    current = ""
+    for line in lines:
+        if current and len(current) + len(line) > max_chars:
+            pieces.append(current)
+            current = ""
+        current += line
+    if current:
+        pieces.append(current)
+    return pieces
+
+
+def assign_speaker_voices(turns, available_voices, overrides=None):
+    """Map each speaker label, in order of first appearance, to a voice.
+
+    Args:
+        turns: Parsed (speaker, text) turns
+        available_voices: Voices to assign in order (e.g. from VOICES)
+        overrides: Optional explicit {speaker: voice} mapping
+
+    Returns:
+        Ordered {speaker: voice} mapping
+    """
+    overrides = overrides or {}
+    speakers = []
+    for speaker, _ in turns:
+        if speaker not in speakers:
+            speakers.append(speaker)
+
+    if len(speakers) > MAX_DIALOGUE_SPEAKERS:
+        raise ValueError(f"Gemini supports at most {MAX_DIALOGUE_SPEAKERS} speakers, found: {', '.join(speakers)}")
+
+    # Each speaker needs a distinct voice: top up a short VOICES list (often a
+    # single voice) with the dialogue defaults
+    fallback_voices = []
+    for voice_name in list(available_voices) + DEFAULT_DIALOGUE_VOICES:
+        if voice_name and voice_name not in fallback_voices and voice_name not in overrides.values():
+            fallback_voices.append(voice_name)
+
+    speaker_voices = {}
+    for speaker in speakers:
+        speaker_voices[speaker] = overrides.get(speaker) or fallback_voices.pop(0)
+    if len(set(speaker_voices.values())) < len(speaker_voices):
+        raise ValueError(f"Speakers must use different voices, got: {speaker_voices}")
+    return speaker_voices
+
+
+def synthesize_dialogue(script, output_file, speaker_voices=None, speakers=None):
+    """Synthesize a labelled two-speaker script into a single WAV file.
+
+    The script is split into pieces under GEMINI_MAX_CHARS (see split_dialogue);
+    every piece uses the same speaker-to-voice mapping so voices stay consistent.
+
+    Args:
+        script: Dialogue script with "Speaker: text" turns
+        output_file: Target WAV path
+        speaker_voices: Optional explicit {speaker: voice} mapping
+        speakers: Speaker labels used in the script (defaults to the mapping's
+            speakers, then the labels of the opening turns)
+
+    Returns:
+        Tuple of (output file, {speaker: voice} mapping used)
+    """
+    turns = parse_dialogue(script, speakers or list(speaker_voices or {}))
+    if not turns:
+        raise ValueError("No 'Speaker: text' lines found in dialogue script")
+
+    speaker_voices = assign_speaker_voices(turns, voices, speaker_voices)
+    logging.info(f"Dialogue speakers: {speaker_voices}")
+    config = build_dialogue_config(speaker_voices)
+
+    pieces = split_dialogue(turns, GEMINI_MAX_CHARS)
+    pcm_data = b""
+    mime_type = None
+    for index, piece in enumerate(pieces):
+        print(f"Synthesizing dialogue piece {index + 1}/{len(pieces)}")
+        piece_data, mime_type = synthesize_piece(f"{DIALOGUE_PROMPT}\n{piece}", "dialogue", config)
+        if pcm_data:
+            pcm_data += pcm_silence(CHUNK_PAUSE_SECONDS, mime_type)
+        pcm_data += piece_data
+
+    save_binary_file(output_file, convert_to_wav(pcm_data, mime_type))
+    return output_file, speaker_voices
+
+
+def generate_dialogue(input_file, output_file, speaker_voices=None):
+    """Generate one conversational audio file from a labelled script file.
+
+    Args:
+        input_file: Script with "Host: ..." / "Guest: ..." lines
+        output_file: Target path; a .mp3 suffix is converted with FFmpeg
+        speaker_voices: Optional explicit {speaker: voice} mapping
+
+    Returns:
+        Path of the written audio file
+    """
+    with open(input_file, "r", encoding="utf-8") as f:
+        script = f.read()
+
+    wav_file = output_file if output_file.lower().endswith(".wav") else f"{os.path.splitext(output_file)[0]}.wav"
+    synthesize_dialogue(script, wav_file, speaker_voices)
+    if wav_file != output_file:
+        try:
+            convert_audio(Path(wav_file), Path(output_file))
+        finally:
+            os.remove(wav_file)
+        if MASTERING_ENABLED:
+            master_audio(Path(output_file))
+    print(f"Dialogue audio written to: {output_file}")
+    return output_file
+
+
+def parse_speaker_overrides(value):
+    """Parse "Host=Puck,Guest=Kore" into {"Host": "Puck", "Guest": "Kore"}."""
+    overrides = {}
+    for pair in (value or "").split(","):
+        if "=" in pair:
+            speaker, voice_name = pair.split("=", 1)
+            overrides[speaker.strip()] = voice_name.strip()
+    return overrides
+
+
+def pcm_silence(seconds, mime_type):
+    """Return raw PCM silence matching the stream's sample format."""
+    parameters = parse_audio_mime_type(mime_type)
+    bytes_per_sample = parameters["bits_per_sample"] // 8
+    frames = int(parameters["rate"] * seconds)
+    return b"\x00" * (frames * bytes_per_sample)
+
+
+def generate(voice_name, input_file="input.txt", output_file=None):
+    """Generate one audio file for a voice from an input text file.
+
+    Args:
+        voice_name: Gemini prebuilt voice name
+        input_file: Text file to narrate
+        output_file: Target path; a .mp3 suffix is converted with FFmpeg
+            (defaults to {voice_name}.wav in the current directory)
+
+    Returns:
+        Path of the written audio file
+    """
+    logging.info(f"Starting generation for voice: {voice_name}")
+
+    with open(input_file, "r", encoding="utf-8") as f:
+        text = f.read()
+
+    if output_file is None:
+        output_file = f"{voice_name}.wav"
+
+    if output_file.lower().endswith(".wav"):
+        return synthesize_to_file(text, voice_name, output_file)
+
+    wav_file = f"{os.path.splitext(output_file)[0]}.wav"
+    synthesize_to_file(text, voice_name, wav_file)
+    try:
+        convert_audio(Path(wav_file), Path(output_file))
+    finally:
+        os.remove(wav_file)
+    if MASTERING_ENABLED:
+        master_audio(Path(output_file))
+    print(f"Audio written to: {output_file}")
+    return output_file
+
+
+def output_path_for(voice_name, bundle=None, multiple_voices=False):
+    """Pick the output file for a voice: narration.mp3 inside a page bundle, or a WAV in the CWD."""
+    if bundle is None:
+        return None
+    file_name = f"narration_{voice_name}.mp3" if multiple_voices else "narration.mp3"
+    return str(Path(bundle) / file_name)
+
 
 def convert_to_wav(audio_data: bytes, mime_type: str) -> bytes:
     """Generates a WAV file header for the given audio data and parameters.
@@ -290,6 +592,14 @@ def save_progress(index):
 
 
 if __name__ == "__main__":
+    # Set up logging
+    logging.basicConfig(
+        filename='gemini_tts.log',
+        level=logging.INFO,
+        format='%(asctime)s - %(levelname)s - %(message)s',
+        datefmt='%Y-%m-%d %H:%M:%S'
+    )
+
     # Parse command line arguments
     parser = argparse.ArgumentParser(description='Gemini TTS Generator with speech control')
     parser.add_argument('--reset', '-r', action='store_true', help='Reset progress and start from beginning')
@@ -297,9 +607,16 @@ if __name__ == "__main__":
     parser.add_argument('--pitch', type=float, help='Voice pitch in semitones (-10 to +10, default: 0.0)')
     parser.add_argument('--volume', type=float, help='Volume gain in dB (-10 to +10, default: 0.0)')
     parser.add_argument('--voice', help='Voice name (overrides VOICES env var)')
+    parser.add_argument('--input', '-i', default='input.txt', help='Text file to narrate (default: input.txt)')
+    parser.add_argument('--bundle', '-b', help='Page bundle directory to write narration.mp3 into (e.g. content/blog/my-post)')
+    parser.add_argument('--dialogue', '-d', action='store_true', help='Treat input as a "Host: ..." / "Guest: ..." script and render one multi-speaker file')
+    parser.add_argument('--speakers', help='Speaker-to-voice mapping for --dialogue, e.g. "Host=Puck,Guest=Kore" (default: assign from VOICES)')
 
     args = parser.parse_args()
 
+    if args.bundle and not Path(args.bundle).is_dir():
+        parser.error(f"Bundle directory not found: {args.bundle}")
+
     # Override speech parameters from command line
     if args.rate is not None:
         speech_rate = args.rate
@@ -314,6 +631,12 @@ if __name__ == "__main__":
         voices = [args.voice]
         print(f"Voice set to: {args.voice}")
 
+    if args.dialogue:
+        output_file = str(Path(args.bundle) / "podcast.mp3") if args.bundle else "dialogue.wav"
+        logging.info(f"Dialogue mode: {args.input} -> {output_file}")
+        generate_dialogue(args.input, output_file, parse_speaker_overrides(args.speakers))
+        sys.exit(0)
+
     # Handle reset flag
     if args.reset:
         try:
@@ -326,8 +649,7 @@ if __name__ == "__main__":
     else:
         start_index = load_progress()
 
-    logging.info(f"Selected API key index: {key_index}, Key: GEMINI_API_KEY_{key_index + 1}")
-    logging.info(f"API key preview: {api_key[:20]}..." if api_key else "API key not found")
+    logging.info(f"Gemini key pool: {len(key_pool)} key(s), health: {key_pool.summary()}")
     logging.info(f"Speech config - Rate: {speech_rate}, Pitch: {pitch}, Volume: {volume_gain_db}dB")
     logging.info(f"Selected voices: {voices}")
     if args.rate is not None or args.pitch is not None or args.volume is not None:
@@ -342,10 +664,9 @@ if __name__ == "__main__":
             voice = voices[i]
             logging.info(f"Processing voice {i}: {voice}")
             print(f"Generating audio for voice: {voice} (index {i})")
-            generate(voice)
+            generate(voice, args.input, output_path_for(voice, args.bundle, len(voices) > 1))
             save_progress(i + 1)  # Save progress after successful generation
             logging.info(f"Completed voice {i}: {voice}, progress saved to index {i + 1}")
-            time.sleep(60)  # Delay to avoid rate limits
     except Exception as e:
         error_msg = f"Error occurred at voice index {i}: {str(e)}"
         logging.error(error_msg)
@@ -0,0 +1,129 @@
+#!/usr/bin/env python3
+"""
+Bundle Narrator
+Regenerates narration.mp3 for existing page bundles from their asset.txt.
+"""
+
+import argparse
+import asyncio
+import logging
+import sys
+from pathlib import Path
+
+from dotenv import load_dotenv
+
+# Import our modular processors
+sys.path.append(str(Path(__file__).parent.parent))
+
+from processors.tts_engine import generate_audio_from_text, validate_audio_file, caption_path_for, TTS_BACKENDS
+from processors.front_matter import set_extra_values
+from processors.audio_metadata import tag_bundle_audio
+from processors.bundle_schema import load_bundle_metadata, record_step, save_bundle_metadata
+from processors.cache import add_cache_arguments, configure_cache
+from processors.config import settings, bundle_dirs
+
+# Setup logging
+logging.basicConfig(
+    level=logging.INFO,
+    format='%(asctime)s - %(levelname)s - %(message)s'
+)
+logger = logging.getLogger(__name__)
+
+# Load environment variables
+load_dotenv()
+
+
+async def narrate_bundle(post_dir: Path, tts_backend: str, voice: str = None, force: bool = False) -> bool:
+    """
+    Generate narration.mp3 (and narration.vtt captions, when supported) for a single page bundle.
+
+    Args:
+        post_dir: Page bundle directory
+        tts_backend: Name of the TTS backend to use
+        voice: Voice for the TTS backend (backend default if None)
+        force: Regenerate even if narration.mp3 already exists
+
+    Returns:
+        True if narration exists after the call, False otherwise
+    """
+    txt_path = post_dir / "asset.txt"
+    mp3_path = post_dir / "narration.mp3"
+
+    if not txt_path.exists():
+        logger.info(f"⏭️ No asset.txt in {post_dir.name}, skipping")
+        return False
+    if mp3_path.exists() and not force:
+        logger.info(f"⏭️ narration.mp3 already exists in {post_dir.name}, skipping")
+        return True
+
+    text = txt_path.read_text(encoding="utf-8").strip()
+    if not text:
+        logger.warning(f"⚠️ Empty asset.txt in {post_dir.name}, skipping")
+        return False
+
+    logger.info(f"🔊 Narrating {post_dir.name} with '{tts_backend}' backend...")
+    try:
+        used_backend = await generate_audio_from_text(text, mp3_path, voice=voice, backend=tts_backend,
+                                                      captions=True)
+    except Exception as e:
+        logger.error(f"❌ Narration failed for {post_dir.name}: {e}")
+        return False
+
+    if not validate_audio_file(mp3_path):
+        return False
+
+    tag_bundle_audio(post_dir, audio_files=["narration.mp3"])
+
+    extra = {"narration": mp3_path.name}
+    if caption_path_for(mp3_path).exists():
+        extra["captions"] = caption_path_for(mp3_path).name
+    set_extra_values(post_dir / "index.md", extra)
+
+    metadata = load_bundle_metadata(post_dir)
+    if metadata is not None:
+        record_step(metadata, "narration", provider=used_backend, voice=voice,
+                    fallback=(used_backend != tts_backend) or None)
+        save_bundle_metadata(metadata, post_dir)
+    return True
+
+
+async def narrate_bundles(post_dirs: list, tts_backend: str, voice: str = None, force: bool = False) -> int:
+    """
+    Generate narration for several bundles sequentially.
+
+    Returns:
+        Number of bundles that failed
+    """
+    failures = 0
+    for post_dir in post_dirs:
+        if not await narrate_bundle(post_dir, tts_backend, voice, force):
+            failures += 1
+    return failures
+
+
+def main():
+    """Main entry point for bundle narration."""
+    parser = argparse.ArgumentParser(description="Regenerate narration.mp3 for page bundles from asset.txt")
+    parser.add_argument("slugs", nargs="*", help="Bundle folder names under content/blog (default: all)")
+    parser.add_argument("--tts-backend", choices=sorted(TTS_BACKENDS),
+                        help="TTS backend for narration (default: narrate_backend in zolamac.toml or 'local')")
+    parser.add_argument("--voice", help="Voice name for the selected TTS backend")
+    parser.add_argument("--force", "-f", action="store_true", help="Overwrite existing narration.mp3 files")
+    add_cache_arguments(parser)
+    args = parser.parse_args()
+    configure_cache(args)
+
+    # Hand-written posts have no asset.txt and are not narrated
+    post_dirs = bundle_dirs(args.slugs, lambda d: (d / "asset.txt").exists())
+
+    tts_backend = args.tts_backend or settings.narrate_backend
+    failures = asyncio.run(narrate_bundles(post_dirs, tts_backend, args.voice or settings.voice, args.force))
+
+    if failures:
+        logger.error(f"💥 {failures} bundle(s) could not be narrated.")
+        sys.exit(1)
+    logger.info("🎉 Narration completed for all bundles!")
+
+
+if __name__ == "__main__":
+    main()
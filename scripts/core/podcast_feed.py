@@ -0,0 +1,117 @@
+#!/usr/bin/env python3
+"""
+Podcast Feed Preparation
+Records each bundle's episode audio (file, byte size, duration) in its front
+matter so templates/podcast.xml can render enclosures. Run before `zola build`.
+"""
+
+import argparse
+import logging
+import sys
+from pathlib import Path
+from typing import Optional
+
+# Import our modular processors
+sys.path.append(str(Path(__file__).parent.parent))
+
+from processors.tts_engine import validate_audio_file, get_audio_duration
+from processors.front_matter import read_front_matter, set_extra_values
+from processors.config import bundle_dirs
+
+# Setup logging
+logging.basicConfig(
+    level=logging.INFO,
+    format='%(asctime)s - %(levelname)s - %(message)s'
+)
+logger = logging.getLogger(__name__)
+
+# Constants
+# Preferred episode audio, best first: the two-host podcast episode, then the
+# narration of the full article, the article reading, and the original source audio
+EPISODE_AUDIO_FILES = ["podcast.mp3", "narration.mp3", "article.mp3", "asset.mp3"]
+EPISODE_IMAGE = "asset.jpg"
+
+
+def find_episode_audio(post_dir: Path) -> Optional[Path]:
+    """
+    Pick the audio file used as a bundle's podcast episode.
+
+    Args:
+        post_dir: Page bundle directory
+
+    Returns:
+        Path to the first valid audio file, or None if the bundle has none
+    """
+    for file_name in EPISODE_AUDIO_FILES:
+        audio_path = post_dir / file_name
+        if audio_path.exists() and validate_audio_file(audio_path):
+            return audio_path
+    return None
+
+
+def update_extra(md_path: Path, values: dict) -> bool:
+    """
+    Set ``[extra]`` keys only if they differ from the front matter, so that
+    unchanged bundles are not rewritten on every build.
+
+    Returns:
+        True if index.md was written
+    """
+    extra = read_front_matter(md_path).get("extra", {})
+    if all(extra.get(key) == value for key, value in values.items()):
+        return False
+    return set_extra_values(md_path, values)
+
+
+def prepare_bundle(post_dir: Path) -> bool:
+    """
+    Record podcast episode details for a single page bundle.
+
+    Sets ``podcast_audio``, ``podcast_length`` (bytes), ``podcast_duration``
+    (seconds) and ``podcast_image`` in ``[extra]``. Bundles without valid
+    audio have these keys removed so they drop out of the feed.
+
+    Args:
+        post_dir: Page bundle directory
+
+    Returns:
+        True if the bundle is part of the feed, False otherwise
+    """
+    md_path = post_dir / "index.md"
+    if not md_path.exists():
+        return False
+
+    audio_path = find_episode_audio(post_dir)
+    duration = get_audio_duration(audio_path) if audio_path else 0.0
+    if not audio_path or duration <= 0:
+        update_extra(md_path, {
+            "podcast_audio": None, "podcast_length": None, "podcast_duration": None, "podcast_image": None,
+        })
+        logger.info(f"⏭️ No valid episode audio in {post_dir.name}, skipping")
+        return False
+
+    episode = {
+        "podcast_audio": audio_path.name,
+        "podcast_length": audio_path.stat().st_size,
+        "podcast_duration": int(round(duration)),
+        "podcast_image": EPISODE_IMAGE if (post_dir / EPISODE_IMAGE).exists() else None,
+    }
+    update_extra(md_path, episode)
+
+    logger.info(f"✅ {post_dir.name}: {audio_path.name} ({episode['podcast_length']} bytes, "
+                f"{episode['podcast_duration']}s)")
+    return True
+
+
+def main():
+    """Main entry point for podcast feed preparation."""
+    parser = argparse.ArgumentParser(description="Record podcast episode audio details in page bundle front matter")
+    parser.add_argument("slugs", nargs="*", help="Bundle folder names under content/blog (default: all)")
+    args = parser.parse_args()
+
+    episodes = sum(prepare_bundle(post_dir) for post_dir in bundle_dirs(args.slugs))
+    logger.info(f"🎙️ {episodes} episode(s) ready for the podcast feed")
+
+
+if __name__ == "__main__":
+    main()
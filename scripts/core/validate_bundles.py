@@ -0,0 +1,97 @@
+#!/usr/bin/env python3
+"""
+Bundle Validator
+Checks every content/blog/*/asset.json against the versioned bundle schema
+and optionally migrates older metadata shapes in place.
+"""
+
+import argparse
+import json
+import logging
+import sys
+from pathlib import Path
+
+# Import our modular processors
+sys.path.append(str(Path(__file__).parent.parent))
+
+from processors.bundle_schema import (
+    METADATA_FILE, SCHEMA_VERSION, migrate_metadata, save_bundle_metadata, validate_metadata, verify_files
+)
+from processors.config import bundle_dirs
+
+# Setup logging
+logging.basicConfig(
+    level=logging.INFO,
+    format='%(asctime)s - %(levelname)s - %(message)s'
+)
+logger = logging.getLogger(__name__)
+
+
+def check_bundle(post_dir: Path, migrate: bool = False) -> bool:
+    """
+    Validate (and optionally migrate) a single bundle's asset.json.
+
+    Args:
+        post_dir: Page bundle directory
+        migrate: Rewrite non-conforming metadata in the current schema
+
+    Returns:
+        True if the bundle conforms (after migration, if requested)
+    """
+    json_path = post_dir / METADATA_FILE
+    if not json_path.exists():
+        logger.info(f"⏭️ {post_dir.name}: no {METADATA_FILE} (hand-written post)")
+        return True
+
+    try:
+        with open(json_path, "r", encoding="utf-8") as f:
+            data = json.load(f)
+    except json.JSONDecodeError as e:
+        logger.error(f"❌ {post_dir.name}: invalid JSON: {e}")
+        return False
+
+    errors = validate_metadata(data)
+    if not errors:
+        errors = verify_files(data, post_dir)
+
+    if not errors:
+        logger.info(f"✅ {post_dir.name}")
+        return True
+
+    if not migrate:
+        logger.warning(f"⚠️ {post_dir.name}:")
+        for error in errors:
+            logger.warning(f"   - {error}")
+        return False
+
+    # Migration also refreshes file sizes, checksums and durations
+    migrated = migrate_metadata(data, post_dir)
+    save_bundle_metadata(migrated, post_dir)
+    remaining = validate_metadata(migrated)
+    if remaining:
+        logger.error(f"❌ {post_dir.name}: still invalid after migration: {'; '.join(remaining)}")
+        return False
+
+    logger.info(f"🔧 {post_dir.name}: migrated to schema v{SCHEMA_VERSION}")
+    return True
+
+
+def main():
+    """Main entry point for bundle validation."""
+    parser = argparse.ArgumentParser(description=f"Validate page bundle {METADATA_FILE} files")
+    parser.add_argument("slugs", nargs="*", help="Bundle folder names under content/blog (default: all)")
+    parser.add_argument("--migrate", action="store_true",
+                        help="Rewrite non-conforming bundles in the current schema")
+    args = parser.parse_args()
+
+    failures = [post_dir.name for post_dir in bundle_dirs(args.slugs) if not check_bundle(post_dir, args.migrate)]
+
+    if failures:
+        hint = "" if args.migrate else " (run with --migrate to fix)"
+        logger.error(f"💥 {len(failures)} bundle(s) do not conform to schema v{SCHEMA_VERSION}{hint}")
+        sys.exit(1)
+    logger.info(f"🎉 All bundles conform to schema v{SCHEMA_VERSION}")
+
+
+if __name__ == "__main__":
+    main()
@@ -4,8 +4,9 @@ Web to Blog Converter
 Main entry point for converting web articles to blog posts with AI narration.
 """
 
+import argparse
 import asyncio
-import json
+import difflib
 import logging
 import os
 import sys
@@ -20,9 +21,21 @@ from pathlib import Path
 sys.path.append(str(Path(__file__).parent.parent))
 
 from processors.content_scraper import fetch_content, slugify, get_content_paths
-from processors.tts_engine import generate_audio_from_text, validate_audio_file
-from processors.image_processor import generate_blog_thumbnail
-from processors.ai_processor import summarize_text_with_groq
+from processors.http_fetcher import RobotsDisallowed
+from processors.slugs import find_bundle_by_source, post_alias, post_id, unique_slug
+from processors.markdown_converter import html_to_markdown
+from processors.tts_engine import (
+    generate_audio_from_text, validate_audio_file, caption_path_for, TTS_BACKENDS
+)
+from processors.front_matter import (
+    build_front_matter, set_extra_values, wrap_generated, has_generated_section, replace_generated_section
+)
+from processors.bundle_schema import new_bundle_metadata, record_step, save_bundle_metadata, load_bundle_metadata
+from processors.cache import add_cache_arguments, configure_cache
+from processors.config import settings
+from processors.audio_metadata import tag_bundle_audio
+from processors.image_processor import generate_blog_thumbnail, BundleImageLocalizer
+from processors.ai_processor import summarize_text_with_groq, has_groq_keys
 
 # Setup logging
 logging.basicConfig(
@@ -36,38 +49,38 @@ load_dotenv()
 GROQ_API_KEY = os.getenv("GROQ_API_KEY")
 UNSPLASH_ACCESS_KEY = os.getenv("UNSPLASH_ACCESS_KEY")
 
-# Constants
-BASE_CONTENT = Path("content/blog")
-OUTPUT_FOLDER = Path("audio_output")
 
-
-def create_zola_markdown(text: str, headings: list, title: str, pub_date: str, md_path: Path) -> None:
+def create_zola_markdown(body: str, title: str, pub_date: str, md_path: Path,
+                         page_meta: Optional[dict] = None, pid: Optional[str] = None) -> None:
     """
     Create Zola markdown post with front matter and content.
 
     Args:
-        text: Main article text
-        headings: List of (tag, heading) tuples
+        body: Article body as Markdown
         title: Article title
         pub_date: Publication date
         md_path: Path to save markdown file
+        page_meta: Page metadata from ``fetch_content`` (authors, description,
+            canonical URL and site name)
+        pid: Stable post ID (adds a ``/p/<id>/`` alias)
     """
-    front_matter = f"""+++
-title = "{title}"
-date = "{pub_date}"
-+++
-
-"""
-
-    md_content = front_matter
-
-    # Add headings as H2 sections
-    for tag, heading in headings:
-        level = int(tag[1])  # h1, h2, h3 -> 1, 2, 3
-        md_content += f"{'#' * level} {heading}\n\n"
-
-    # Add main content
-    md_content += text
+    page_meta = page_meta or {}
+    front_matter = build_front_matter(
+        {
+            "title": title,
+            "date": pub_date,
+            "description": page_meta.get("description"),
+            "authors": page_meta.get("authors"),
+            "aliases": [post_alias(pid)] if pid else None,
+        },
+        {
+            "post_id": pid,
+            "canonical_url": page_meta.get("canonical_url"),
+            "source_site": page_meta.get("source_site"),
+        },
+    )
+
+    md_content = front_matter + wrap_generated(body)
 
     with open(md_path, "w", encoding="utf-8") as f:
         f.write(md_content)
@@ -75,19 +88,6 @@ date = "{pub_date}"
     logger.info(f"✅ Zola post created: {md_path}")
 
 
-def save_metadata(metadata: dict, json_path: Path) -> None:
-    """
-    Save processing metadata to JSON file.
-
-    Args:
-        metadata: Dictionary containing processing metadata
-        json_path: Path to save JSON file
-    """
-    with open(json_path, "w", encoding="utf-8") as f:
-        json.dump(metadata, f, indent=2, ensure_ascii=False)
-    logger.info(f"✅ Metadata saved: {json_path}")
-
-
 def save_raw_text(text: str, txt_path: Path) -> None:
     """
     Save raw extracted text to file.
@@ -101,78 +101,170 @@ def save_raw_text(text: str, txt_path: Path) -> None:
     logger.info(f"✅ Raw text saved: {txt_path}")
 
 
-async def process_web_article(url: str) -> bool:
+def describe_changes(old_text: str, new_text: str) -> str:
+    """Summarize a line diff between two versions of asset.txt (e.g. "+3/-1 lines")."""
+    added = removed = 0
+    for line in difflib.ndiff(old_text.splitlines(), new_text.splitlines()):
+        if line.startswith("+ "):
+            added += 1
+        elif line.startswith("- "):
+            removed += 1
+    return f"+{added}/-{removed} lines"
+
+
+async def process_web_article(url: str, tts_backend: Optional[str] = None, voice: Optional[str] = None,
+                              force: bool = False) -> bool:
     """
     Process a web article into a blog post with audio narration.
 
+    If the URL was imported before (by ``source.url`` in asset.json), the
+    existing bundle is updated instead: nothing is regenerated when the
+    extracted text matches asset.txt; otherwise the text, the generated
+    section of index.md and the narration are refreshed, while the front
+    matter, hand-written text outside the generated section and the
+    thumbnail are kept.
+
     Args:
         url: URL of the web article to process
+        tts_backend: Name of the TTS backend used for narration (settings default if None)
+        voice: Voice for the TTS backend (settings or backend default if None)
+        force: Regenerate every artifact of an existing bundle, overwriting index.md
 
     Returns:
         True if processing successful, False otherwise
     """
+    tts_backend = tts_backend or settings.tts_backend
+    voice = voice or settings.voice
     try:
         logger.info(f"🚀 Starting web article processing: {url}")
 
         # 1. Scrape content from web
         logger.info("📄 Scraping web content...")
-        text, headings, title, pub_date = fetch_content(url)
+        try:
+            text, headings, title, pub_date, article_html, page_meta = fetch_content(url)
+        except RobotsDisallowed as e:
+            logger.error(f"🚫 {e} (set respect_robots = false under [fetch] to override)")
+            return False
 
         if not text.strip():
             logger.error("❌ No content extracted from URL")
             return False
 
-        # 2. Generate slug and create directories
-        slug = slugify(url)
+        # 2. Generate slug and create directories (a re-import reuses the source's bundle)
+        existing = find_bundle_by_source(url=url)
+        if existing:
+            slug = existing.relative_to(settings.content_root).as_posix()
+        else:
+            slug = unique_slug(slugify(url, title), url=url)
+        pid = post_id(url=url)
         paths = get_content_paths(slug)
-        post_dir = BASE_CONTENT / slug
-
-        logger.info(f"📁 Processing post: {slug}")
+        post_dir = settings.content_root / slug
+        previous = load_bundle_metadata(post_dir) if existing else None
+        update = previous is not None and not force
+
+        if update:
+            old_text = paths["txt"].read_text(encoding="utf-8") if paths["txt"].exists() else ""
+            if old_text == text:
+                logger.info(f"✅ {slug} is up to date; the page content has not changed")
+                last_step = previous["pipeline"][-1] if previous["pipeline"] else {}
+                if last_step.get("name") == "update" and last_step.get("status") == "skipped":
+                    previous["pipeline"].pop()  # Keep only the latest unchanged check
+                record_step(previous, "update", status="skipped", reason="unchanged")
+                save_bundle_metadata(previous, post_dir)
+                return True
+            logger.info(f"🔁 Updating {slug}: content changed ({describe_changes(old_text, text)})")
+        else:
+            logger.info(f"📁 Processing post: {slug}")
 
         # 3. Save raw text
         save_raw_text(text, paths["txt"])
 
         # 4. Generate AI summary for TTS (shorter for audio)
         logger.info("🧠 Generating AI summary for audio narration...")
-        if GROQ_API_KEY:
+        if has_groq_keys(GROQ_API_KEY):
             summary = summarize_text_with_groq(text, GROQ_API_KEY, summary_ratio=0.3)
         else:
             summary = text  # Use original text if no API key
 
-        # 5. Generate thumbnail
-        logger.info("🖼️ Generating blog thumbnail...")
-        generate_blog_thumbnail(text, title, post_dir, slug, GROQ_API_KEY, UNSPLASH_ACCESS_KEY)
-
-        # 6. Create Zola markdown post
-        logger.info("📝 Creating Zola markdown post...")
-        create_zola_markdown(text, headings, title, pub_date, paths["md"])
+        # 5. Generate thumbnail (updates keep the existing one, which may have been replaced by hand)
+        if update and (post_dir / "asset.jpg").exists():
+            logger.info("🖼️ Keeping existing thumbnail")
+        else:
+            logger.info("🖼️ Generating blog thumbnail...")
+            if force:
+                (post_dir / "asset.jpg").unlink(missing_ok=True)
+            generate_blog_thumbnail(text, title, post_dir, slug, GROQ_API_KEY, UNSPLASH_ACCESS_KEY)
+
+        # 6. Create Zola markdown post (updates only replace the generated section)
+        images = BundleImageLocalizer(post_dir)
+        markdown_status = "ok"
+        if update and paths["md"].exists() and not has_generated_section(paths["md"]):
+            logger.warning("⚠️ index.md has no generated section (imported before markers existed); "
+                           "leaving it as is, use --force to regenerate it")
+            markdown_status = "skipped"
+        else:
+            logger.info("📝 Creating Zola markdown post...")
+            body = html_to_markdown(article_html, url, image_resolver=images)
+            if not body:
+                logger.warning("⚠️ Markdown conversion produced nothing, using plain text")
+                body = "\n\n".join(line for line in text.splitlines() if line.strip()) + "\n"
+            if update and paths["md"].exists():
+                replace_generated_section(paths["md"], body)
+                logger.info(f"✅ Generated section updated: {paths['md']}")
+            else:
+                create_zola_markdown(body, title, pub_date, paths["md"], page_meta, pid)
+            images.prune(paths["md"])
 
         # 7. Generate audio narration
         logger.info("🔊 Generating audio narration...")
-        await generate_audio_from_text(summary, paths["mp3"])
+        used_backend = await generate_audio_from_text(summary, paths["mp3"], voice=voice, backend=tts_backend,
+                                                      captions=True)
+        captions_path = caption_path_for(paths["mp3"])
 
         # Validate audio file
         if not validate_audio_file(paths["mp3"]):
             logger.warning("⚠️ Audio file validation failed, but continuing...")
+        else:
+            # Tag audio from the front matter, with chapters at the headings the narration kept
+            tag_bundle_audio(post_dir, audio_files=[paths["mp3"].name], outline=(summary, headings), source_url=url)
+
+            # Narration player (and read-along captions) are rendered by page.html
+            extra = {"narration": paths["mp3"].name}
+            if captions_path.exists():
+                extra["captions"] = captions_path.name
+            set_extra_values(paths["md"], extra)
 
         # 8. Save metadata
-        metadata = {
-            "url": url,
-            "slug": slug,
-            "title": title,
-            "pub_date": pub_date,
-            "processing_date": str(Path.cwd()),
-            "audio_file": str(paths["mp3"]),
-            "text_file": str(paths["txt"]),
-            "markdown_file": str(paths["md"]),
-            "thumbnail_file": str(post_dir / "asset.jpg"),
+        metadata = new_bundle_metadata(
+            slug, title, "web", url, page_meta.get("lang"), published=pub_date, modified=page_meta.get("modified"),
+            authors=page_meta.get("authors"), site=page_meta.get("source_site"),
+            canonical_url=page_meta.get("canonical_url"), metadata_sources=page_meta.get("sources"), post_id=pid,
+        )
+        if update:
+            metadata["created_at"] = previous.get("created_at", metadata["created_at"])
+            record_step(metadata, "update", changes=describe_changes(old_text, text))
+        record_step(metadata, "scrape", provider=page_meta.get("fetched_with", "requests"))
+        summarized = has_groq_keys(GROQ_API_KEY)
+        record_step(metadata, "summarize", provider="groq" if summarized else None,
+                    status="ok" if summarized else "skipped")
+        record_step(metadata, "thumbnail", status="ok" if (post_dir / "asset.jpg").exists() else "failed")
+        record_step(metadata, "markdown", status=markdown_status)
+        if markdown_status == "ok":
+            record_step(metadata, "images", status="failed" if images.failed and not images.records else "ok",
+                        count=len(images.records), remote=len(images.failed) or None)
+        record_step(metadata, "tts", provider=used_backend, voice=voice,
+                    status="ok" if paths["mp3"].exists() else "failed",
+                    fallback=(used_backend != tts_backend) or None)
+        record_step(metadata, "captions", status="ok" if captions_path.exists() else "skipped")
+        metadata["content"].update({
             "content_length": len(text),
-            "headings_count": len(headings)
-        }
-        save_metadata(metadata, paths["json"])
+            "headings_count": len(headings),
+            "images": images.records if markdown_status == "ok" else previous["content"].get("images", []),
+        })
+        save_bundle_metadata(metadata, post_dir)
 
         logger.info("✅ Web article processing completed successfully!")
-        logger.info(f"📄 Post available at: content/blog/{slug}/index.md")
+        logger.info(f"📄 Post available at: {post_dir / 'index.md'}")
         return True
 
     except Exception as e:
@@ -182,12 +274,18 @@ async def process_web_article(url: str) -> bool:
 
 def main():
     """Main entry point for web-to-blog conversion."""
-    if len(sys.argv) < 2:
-        logger.error("❌ Usage: python web_to_blog.py <url>")
-        logger.info("Example: python web_to_blog.py https://example.com/article")
-        sys.exit(1)
-
-    url = sys.argv[1]
+    parser = argparse.ArgumentParser(description="Convert a web article into a Zola blog post with audio narration")
+    parser.add_argument("url", help="URL of the web article, e.g. https://example.com/article")
+    parser.add_argument("--tts-backend", choices=sorted(TTS_BACKENDS),
+                        help="TTS backend for narration (default: zolamac.toml, TTS_BACKEND env or 'edge')")
+    parser.add_argument("--voice", help="Voice name for the selected TTS backend")
+    parser.add_argument("--force", action="store_true",
+                        help="Regenerate an already imported article from scratch, overwriting index.md edits")
+    add_cache_arguments(parser)
+    args = parser.parse_args()
+    configure_cache(args)
+
+    url = args.url
 
     # Validate URL format
     if not url.startswith(('http://', 'https://')):
@@ -195,17 +293,17 @@ def main():
         sys.exit(1)
 
     # Ensure required directories exist
-    BASE_CONTENT.mkdir(parents=True, exist_ok=True)
-    OUTPUT_FOLDER.mkdir(exist_ok=True)
+    settings.content_root.mkdir(parents=True, exist_ok=True)
+    settings.output_folder.mkdir(exist_ok=True)
 
     # Check for API keys
-    if not GROQ_API_KEY:
-        logger.warning("⚠️ GROQ_API_KEY not found. Audio summarization will be skipped.")
+    if not has_groq_keys(GROQ_API_KEY):
+        logger.warning("⚠️ GROQ_API_KEY (or GROQ_API_KEY_<n>) not found. Audio summarization will be skipped.")
     if not UNSPLASH_ACCESS_KEY:
         logger.warning("⚠️ UNSPLASH_ACCESS_KEY not found. Thumbnail generation may be limited.")
 
     # Process the article
-    success = asyncio.run(process_web_article(url))
+    success = asyncio.run(process_web_article(url, args.tts_backend, args.voice, args.force))
 
     if success:
         logger.info("🎉 Processing completed successfully!")
@@ -4,13 +4,16 @@ YouTube to Blog Converter
 Main entry point for converting YouTube videos to blog posts with AI narration.
 """
 
+import argparse
 import asyncio
+import html
 import json
 import logging
 import os
+import re
 import sys
 from pathlib import Path
-from typing import Optional
+from typing import List, Optional
 
 from dotenv import load_dotenv
 
@@ -20,15 +23,24 @@ from pathlib import Path
 sys.path.append(str(Path(__file__).parent.parent))
 
 from processors.youtube_processor import (
-    fetch_youtube_info, download_audio, validate_youtube_url,
-    parse_upload_date, format_duration, sanitize_text, download_youtube_subtitles
+    fetch_youtube_info, download_audio, validate_youtube_url, is_youtube_collection_url, fetch_youtube_collection,
+    parse_upload_date, format_duration, format_timestamp, sanitize_text, download_youtube_subtitles,
+    segments_to_text, group_segments, subtitle_tracks, translation_track, original_language
 )
 from processors.ai_processor import (
     transcribe_audio_with_groq, generate_ai_summary_and_structure,
-    generate_final_article, generate_social_media_post
+    generate_final_article, generate_social_media_post, generate_podcast_script, has_groq_keys
 )
 from processors.image_processor import generate_blog_thumbnail, download_youtube_thumbnail
-from processors.tts_engine import generate_audio_from_text, validate_audio_file
+from processors.tts_engine import (
+    generate_audio_from_text, validate_audio_file, caption_path_for, TTS_BACKENDS, GeminiTTSBackend
+)
+from processors.front_matter import build_front_matter, set_extra_values
+from processors.bundle_schema import new_bundle_metadata, record_step, save_bundle_metadata
+from processors.cache import add_cache_arguments, configure_cache
+from processors.config import settings
+from processors.audio_metadata import tag_bundle_audio, markdown_headings
+from processors.slugs import imported_youtube_ids, make_slug, post_alias, post_id, unique_slug
 
 # Setup logging
 logging.basicConfig(
@@ -42,22 +54,78 @@ load_dotenv()
 GROQ_API_KEY = os.getenv("GROQ_API_KEY")
 UNSPLASH_ACCESS_KEY = os.getenv("UNSPLASH_ACCESS_KEY")
 
-# Constants
-BASE_CONTENT = Path("content/blog")
-OUTPUT_FOLDER = Path("audio_output")
+
+def youtube_time_url(video_id: str, seconds: float) -> str:
+    """Watch URL that starts the video at a position."""
+    return f"https://www.youtube.com/watch?v={video_id}&t={int(seconds)}s"
+
+
+def seek_link(video_id: str, seconds: float, css_class: Optional[str] = None) -> str:
+    """
+    Timestamp link that seeks the embedded player (see page.html) or, without
+    JavaScript, opens YouTube at that position.
+    """
+    class_attr = f' class="{css_class}"' if css_class else ""
+    return (f'<a href="{html.escape(youtube_time_url(video_id, seconds))}" data-seek="{int(seconds)}"{class_attr}>'
+            f'{format_timestamp(seconds)}</a>')
+
+
+def render_chapters(chapters: List[dict], video_id: str) -> str:
+    """Chapter list as a table of contents of timestamp links."""
+    items = [
+        f'<li>{seek_link(video_id, chapter.get("start_time", 0))} {html.escape(chapter.get("title") or "")}</li>'
+        for chapter in chapters
+    ]
+    return '<ol class="video-chapters">\n' + "\n".join(items) + "\n</ol>\n\n"
+
+
+def render_transcript(transcript_text: str, segments: Optional[List[dict]], chapters: List[dict],
+                      video_id: Optional[str]) -> str:
+    """
+    Full transcript as a collapsible block.
+
+    Timed segments become timestamped paragraphs (with chapter headings);
+    a plain transcript (e.g. from Whisper) becomes untimed paragraphs.
+    """
+    blocks = []
+    if segments and video_id:
+        starts = [chapter.get("start_time", 0) for chapter in chapters] or [0]
+        for index, chapter_start in enumerate(starts):
+            chapter_end = starts[index + 1] if index + 1 < len(starts) else float("inf")
+            # Segments before the first chapter belong to it
+            in_chapter = [seg for seg in segments
+                          if (seg["start"] >= chapter_start or index == 0) and seg["start"] < chapter_end]
+            if not in_chapter:
+                continue
+            if chapters:
+                blocks.append(f"<h3>{html.escape(chapters[index].get('title') or '')}</h3>")
+            blocks += [f'<p>{seek_link(video_id, paragraph["start"], "transcript-time")} '
+                       f'{html.escape(paragraph["text"])}</p>'
+                       for paragraph in group_segments(in_chapter)]
+    else:
+        sentences = re.split(r"(?<=[.!?。！？])\s+", transcript_text.strip())
+        blocks = [f"<p>{html.escape(' '.join(sentences[i:i + 5]))}</p>" for i in range(0, len(sentences), 5)]
+
+    words = len(transcript_text.split())
+    return ('<details class="video-transcript">\n'
+            f"<summary>Show the full transcript ({words:,} words)</summary>\n"
+            + "\n".join(blocks) + "\n</details>\n\n")
 
 
 def create_youtube_markdown(metadata: dict, transcript_text: str, has_audio: bool = False, ai_structure: Optional[str] = None,
-                           final_article: Optional[str] = None, social_post: Optional[str] = None) -> str:
+                           final_article: Optional[str] = None, social_post: Optional[str] = None,
+                           pid: Optional[str] = None, segments: Optional[List[dict]] = None) -> str:
     """
     Create Zola markdown post for YouTube video with full content.
 
     Args:
         metadata: YouTube video metadata
         transcript_text: Video transcript
+        segments: Timed transcript segments from the video's subtitles
         ai_structure: AI-generated summary structure
         final_article: AI-generated full article
         social_post: Social media post text
+        pid: Stable post ID (adds a ``/p/<id>/`` alias)
 
     Returns:
         Complete markdown content
@@ -74,11 +142,13 @@ def create_youtube_markdown(metadata: dict, transcript_text: str, has_audio: boo
     tags = metadata.get("tags", [])[:5]
     tags_str = ", ".join([f'"{tag}"' for tag in tags]) if tags else '"youtube", "video", "tutorial"'
 
+    id_lines = f'\naliases = ["{post_alias(pid)}"]\n\n[extra]\npost_id = "{pid}"' if pid else ""
+
     # Front matter
     front_matter = f"""+++
 title = "{title}"
 date = "{upload_date}"
-tags = [{tags_str}]
+tags = [{tags_str}]{id_lines}
 +++
 
 """
@@ -110,15 +180,16 @@ tags = [{tags_str}]
         content += f"## 📋 Structured Key Takeaways\n\n{ai_structure}\n\n---\n\n"
 
     # Transcript
+    video_id = metadata.get("id")
+    chapters = metadata.get("chapters") or []
     if transcript_text and transcript_text != "ERROR: Audio transcription failed after all retries.":
-        content += f"## 📝 Transcript (Auto-Generated)\n\n> {transcript_text[:1000]}...\n\n---\n\n"
+        content += "## 📝 Transcript\n\n" + render_transcript(transcript_text, segments, chapters, video_id) + "---\n\n"
 
     # Full article
     if final_article:
         content += f"## 📝 Full Article Narrative\n\n{final_article}\n\n---\n\n"
 
-    # Video embed
-    video_id = metadata.get("id")
+    # Video embed (enablejsapi lets chapter and transcript links seek the player)
     if video_id:
         content += f"""## ▶️ Watch the Video
 
@@ -126,12 +197,14 @@ tags = [{tags_str}]
 * **Duration:** {duration}
 
 <div class="youtube-embed">
-<iframe width="560" height="315" src="https://www.youtube.com/embed/{video_id}" frameborder="0" allowfullscreen></iframe>
+<iframe id="youtube-player" width="560" height="315" src="https://www.youtube.com/embed/{video_id}?enablejsapi=1" frameborder="0" allowfullscreen></iframe>
 </div>
 
 [Watch on YouTube]({metadata.get("webpage_url", f"https://www.youtube.com/watch?v={video_id}")})
 
 """
+        if chapters:
+            content += "### 📑 Chapters\n\n" + render_chapters(chapters, video_id)
 
     # Social media post
     if social_post:
@@ -146,17 +219,57 @@ tags = [{tags_str}]
     return content
 
 
-def save_metadata(metadata: dict, json_path: Path) -> None:
+def save_timed_transcript(segments: List[dict], txt_path: Path, json_path: Path) -> None:
+    """Save a subtitle transcript as plain text and as timed segments."""
+    save_raw_text(segments_to_text(segments), txt_path)
+    with open(json_path, "w", encoding="utf-8") as f:
+        json.dump(segments, f, ensure_ascii=False)
+
+
+def download_translated_transcript(metadata: dict, video_id: str, post_dir: Path, source_language: str) -> Optional[str]:
     """
-    Save processing metadata to JSON file.
+    Store YouTube's translation of the transcript next to it (``subtitles.translate_to``).
+
+    Returns:
+        Name of the translated transcript file, or None if none was saved
+    """
+    target = settings.subtitle_translate_to
+    if not target:
+        return None
+    track = translation_track(metadata, target, source_language)
+    if not track:
+        if source_language.split("-")[0].lower() != target.split("-")[0].lower():
+            logger.info(f"ℹ️ YouTube offers no {target} translation of the subtitles")
+        return None
+
+    logger.info(f"🌐 Downloading {target} translation of the {source_language} subtitles...")
+    segments = download_youtube_subtitles(video_id, post_dir, track)
+    if not segments:
+        logger.warning(f"⚠️ Could not download the {target} translation")
+        return None
+    txt_path = post_dir / f"transcript.{track['language']}.txt"
+    save_timed_transcript(segments, txt_path, post_dir / f"transcript.{track['language']}.json")
+    return txt_path.name
+
+
+def append_podcast_player(md_path: Path) -> None:
+    """
+    Append a player for the two-host podcast episode to a post.
 
     Args:
-        metadata: Dictionary containing processing metadata
-        json_path: Path to save JSON file
+        md_path: Path to the post's index.md
     """
-    with open(json_path, "w", encoding="utf-8") as f:
-        json.dump(metadata, f, indent=2, ensure_ascii=False)
-    logger.info(f"✅ Metadata saved: {json_path}")
+    with open(md_path, "a", encoding="utf-8") as f:
+        f.write("""
+## 🎙️ Podcast Episode (Two Hosts)
+
+<audio controls style="width: 100%;">
+  <source src="podcast.mp3" type="audio/mpeg">
+  Your browser does not support the audio element.
+</audio>
+
+""")
+    logger.info(f"✅ Podcast player added to {md_path}")
 
 
 def save_raw_text(text: str, txt_path: Path) -> None:
@@ -172,16 +285,23 @@ def save_raw_text(text: str, txt_path: Path) -> None:
     logger.info(f"✅ Raw text saved: {txt_path}")
 
 
-async def process_youtube_video(url: str) -> bool:
+async def process_youtube_video(url: str, tts_backend: Optional[str] = None, voice: Optional[str] = None,
+                                podcast: bool = False, section: Optional[str] = None) -> bool:
     """
     Process a YouTube video into a blog post with AI narration.
 
     Args:
         url: YouTube video URL
+        tts_backend: Name of the TTS backend used for narration (settings default if None)
+        voice: Voice for the TTS backend (settings or backend default if None)
+        podcast: Also render the article as a two-host Gemini podcast episode
+        section: Series section folder (under the content root) for a new bundle
 
     Returns:
         True if processing successful, False otherwise
     """
+    tts_backend = tts_backend or settings.tts_backend
+    voice = voice or settings.voice
     post_dir = None
     try:
         logger.info(f"🚀 Starting YouTube video processing: {url}")
@@ -205,9 +325,10 @@ async def process_youtube_video(url: str) -> bool:
 
         # 3. Generate slug and create directories
         from processors.content_scraper import slugify, get_content_paths
-        slug = slugify(metadata.get("title", "youtube-video"))
+        slug = unique_slug(slugify(metadata.get("title") or f"youtube-{video_id}"), video_id=video_id, section=section)
+        pid = post_id(video_id=video_id)
         paths = get_content_paths(slug)
-        post_dir = BASE_CONTENT / slug
+        post_dir = settings.content_root / slug
 
         logger.info(f"📁 Processing video: {slug}")
 
@@ -224,20 +345,29 @@ async def process_youtube_video(url: str) -> bool:
         logger.info("📝 Getting transcript...")
         transcript = ""
 
-        # First try YouTube subtitles
-        transcript_content = None
-        if video_id:
-            logger.info("🎭 Checking for YouTube subtitles/captions...")
-            transcript_content = download_youtube_subtitles(video_id, post_dir)
-
-        if transcript_content:
-            transcript = transcript_content
-            save_raw_text(transcript, paths["txt"])
-            logger.info("📄 Saved YouTube transcript to asset.txt")
+        # First try YouTube subtitles, in the video's own language if it has them
+        transcript_segments = None
+        subtitle_track = None
+        translated_transcript = None
+        for candidate in subtitle_tracks(metadata) if video_id else []:
+            kind = "automatic captions" if candidate["automatic"] else "subtitles"
+            logger.info(f"🎭 Downloading {candidate['language']} {kind}...")
+            transcript_segments = download_youtube_subtitles(video_id, post_dir, candidate)
+            if transcript_segments:
+                subtitle_track = candidate
+                break
+
+        if transcript_segments:
+            transcript = segments_to_text(transcript_segments)
+            save_timed_transcript(transcript_segments, paths["txt"], post_dir / "transcript.json")
+            logger.info("📄 Saved YouTube transcript to asset.txt and timed segments to transcript.json")
+            translated_transcript = download_translated_transcript(
+                metadata, video_id, post_dir, subtitle_track["language"]
+            )
         else:
             # Fallback to AI transcription
             logger.info("ℹ️ No YouTube subtitles available, using AI transcription...")
-            if audio_downloaded and GROQ_API_KEY:
+            if audio_downloaded and has_groq_keys(GROQ_API_KEY):
                 transcript = transcribe_audio_with_groq(str(paths["mp3"]), GROQ_API_KEY)
                 if transcript.startswith("ERROR:"):
                     logger.warning(f"⚠️ AI transcription failed: {transcript}")
@@ -288,7 +418,7 @@ async def process_youtube_video(url: str) -> bool:
             content_for_ai = f"{video_title}\n\n{video_description}"
             logger.info("🧠 Generating AI content from video description...")
 
-        if content_for_ai.strip() and GROQ_API_KEY:
+        if content_for_ai.strip() and has_groq_keys(GROQ_API_KEY):
             # Generate structured summary
             ai_structure = generate_ai_summary_and_structure(
                 metadata.get("title", ""),
@@ -345,14 +475,27 @@ async def process_youtube_video(url: str) -> bool:
         # Check if we have valid audio
         has_audio = audio_downloaded and validate_audio_file(paths["mp3"])
         markdown_content = create_youtube_markdown(
-            metadata, transcript, has_audio, ai_structure, final_article, social_post
+            metadata, transcript, has_audio, ai_structure, final_article, social_post, pid, transcript_segments
         )
 
         with open(paths["md"], "w", encoding="utf-8") as f:
             f.write(markdown_content)
 
+        # Record which language the transcript is in (Whisper transcribes the spoken language)
+        if transcript_segments:
+            transcript_language = subtitle_track["language"]
+        else:
+            transcript_language = original_language(metadata) if transcript else None
+        set_extra_values(paths["md"], {
+            "transcript_language": transcript_language,
+            "translated_transcript": translated_transcript,
+        })
+
         # 9. Generate audio narration (if we have content to narrate)
         narration_text = ""
+        used_backend = None
+        narration_path = post_dir / "narration.mp3"
+        captions_path = caption_path_for(narration_path)
         if final_article:
             narration_text = final_article
         elif ai_structure:
@@ -360,40 +503,59 @@ async def process_youtube_video(url: str) -> bool:
         elif transcript:
             narration_text = transcript[:2000]  # Limit for TTS
 
-        if narration_text and GROQ_API_KEY:
+        summary = ""
+        if narration_text and has_groq_keys(GROQ_API_KEY):
             logger.info("🔊 Generating AI narration...")
             # Create a summary for narration
-            summary = ""
-            if GROQ_API_KEY:
+            if has_groq_keys(GROQ_API_KEY):
                 from processors.ai_processor import summarize_text_with_groq
                 summary = summarize_text_with_groq(narration_text, GROQ_API_KEY, summary_ratio=0.4)
 
             if summary:
-                await generate_audio_from_text(summary, paths["mp3"].parent / "narration.mp3")
+                used_backend = await generate_audio_from_text(
+                    summary, narration_path, voice=voice, backend=tts_backend, captions=True
+                )
+                if validate_audio_file(narration_path):
+                    extra = {"narration": narration_path.name}
+                    if captions_path.exists():
+                        extra["captions"] = captions_path.name
+                    set_extra_values(paths["md"], extra)
+
+        # Two-host podcast episode from the final article
+        podcast_file = None
+        if podcast and final_article:
+            podcast_speakers = ("Host", "Guest")
+            podcast_script = generate_podcast_script(metadata.get("title", ""), final_article, GROQ_API_KEY,
+                                                     podcast_speakers)
+            if podcast_script:
+                save_raw_text(podcast_script, post_dir / "podcast.txt")
+                podcast_path = post_dir / "podcast.mp3"
+                try:
+                    logger.info("🎙️ Generating two-host podcast episode...")
+                    await GeminiTTSBackend().synthesize_dialogue(podcast_script, podcast_path,
+                                                                 speakers=list(podcast_speakers))
+                    append_podcast_player(paths["md"])
+                    podcast_file = str(podcast_path)
+                except Exception as e:
+                    logger.warning(f"⚠️ Podcast generation failed, continuing without it: {e}")
 
         # Validate audio file
         if not validate_audio_file(paths["mp3"]):
             logger.warning("⚠️ Audio file validation failed, but continuing...")
 
-        # 10. Save metadata
-        processing_metadata = {
-            "url": url,
-            "video_id": video_id,
-            "slug": slug,
-            "title": metadata.get("title"),
-            "uploader": metadata.get("uploader"),
-            "duration": metadata.get("duration"),
-            "upload_date": metadata.get("upload_date"),
-            "view_count": metadata.get("view_count"),
-            "processing_date": str(Path.cwd()),
-            "audio_file": str(paths["mp3"]),
-            "transcript_length": len(transcript) if transcript else 0,
-            "has_ai_content": bool(ai_structure or final_article),
-            "thumbnail_file": str(post_dir / "asset.jpg"),
-        }
-        save_metadata(processing_metadata, paths["json"])
-
-        # 11. Clean up temporary files
+        # Tag audio files from the front matter, with the video's chapters for its audio
+        # and chapters at the headings the narrated summary kept (the podcast dialogue
+        # rewrites the article, so it gets none)
+        uploader = metadata.get("uploader")
+        video_url = metadata.get("webpage_url") or url
+        if has_audio:
+            chapters = [(c.get("start_time", 0), c.get("title", "")) for c in metadata.get("chapters", [])]
+            tag_bundle_audio(post_dir, chapters, [paths["mp3"].name], author=uploader, source_url=video_url)
+        tag_bundle_audio(post_dir, audio_files=[narration_path.name],
+                         outline=(summary, markdown_headings(narration_text)), author=uploader, source_url=video_url)
+        tag_bundle_audio(post_dir, audio_files=["podcast.mp3"], author=uploader, source_url=video_url)
+
+        # 10. Clean up temporary files
         logger.info("🧹 Cleaning up temporary files...")
         import shutil
 
@@ -417,8 +579,46 @@ async def process_youtube_video(url: str) -> bool:
 
         logger.info("✅ All temporary files cleaned up")
 
+        # 11. Save metadata (after cleanup, so only final bundle files are recorded)
+        bundle_metadata = new_bundle_metadata(
+            slug, metadata.get("title") or slug, "youtube", url, transcript_language,
+            id=video_id,
+            post_id=pid,
+            author=metadata.get("uploader"),
+            published=parse_upload_date(metadata.get("upload_date", "")) or None,
+            duration=metadata.get("duration"),
+            view_count=metadata.get("view_count"),
+        )
+        record_step(bundle_metadata, "download_audio", provider="yt-dlp",
+                    status="ok" if audio_downloaded else "failed")
+        if transcript_segments:
+            record_step(bundle_metadata, "transcript", provider="youtube", segments=len(transcript_segments),
+                        language=subtitle_track["language"], automatic=subtitle_track["automatic"])
+            if settings.subtitle_translate_to:
+                record_step(bundle_metadata, "translation", provider="youtube",
+                            language=settings.subtitle_translate_to,
+                            status="ok" if translated_transcript else "skipped")
+        elif transcript:
+            record_step(bundle_metadata, "transcript", provider="groq")
+        else:
+            record_step(bundle_metadata, "transcript", status="skipped", fallback="description")
+        record_step(bundle_metadata, "article", provider="groq" if final_article else None,
+                    status="ok" if final_article else "skipped")
+        record_step(bundle_metadata, "thumbnail", status="ok" if (post_dir / "asset.jpg").exists() else "failed")
+        record_step(bundle_metadata, "tts", provider=used_backend, voice=voice,
+                    status="ok" if used_backend else "skipped",
+                    fallback=(used_backend not in (None, tts_backend)) or None)
+        record_step(bundle_metadata, "captions", status="ok" if captions_path.exists() else "skipped")
+        if podcast:
+            record_step(bundle_metadata, "podcast", provider="gemini", status="ok" if podcast_file else "failed")
+        bundle_metadata["content"].update({
+            "transcript_length": len(transcript) if transcript else 0,
+            "has_ai_content": bool(ai_structure or final_article),
+        })
+        save_bundle_metadata(bundle_metadata, post_dir)
+
         logger.info("✅ YouTube video processing completed successfully!")
-        logger.info(f"📄 Post available at: content/blog/{slug}/index.md")
+        logger.info(f"📄 Post available at: {post_dir / 'index.md'}")
         return True
 
     except Exception as e:
@@ -450,14 +650,158 @@ async def process_youtube_video(url: str) -> bool:
                 logger.info(f"🗑️ Removed temp file: {temp_file.name}")
 
 
+def add_collection_arguments(parser: argparse.ArgumentParser) -> None:
+    """Add the playlist and channel options (``--after``, ``--before``, ``--max``) to a parser."""
+    parser.add_argument("--after", type=parse_date_option, metavar="DATE",
+                        help="Playlists/channels: only videos uploaded on or after DATE (YYYY-MM-DD)")
+    parser.add_argument("--before", type=parse_date_option, metavar="DATE",
+                        help="Playlists/channels: only videos uploaded on or before DATE (YYYY-MM-DD)")
+    parser.add_argument("--max", dest="max_videos", type=int, metavar="N",
+                        help="Playlists/channels: import at most N new videos")
+
+
+def create_series_index(section_dir: Path, collection: dict) -> Path:
+    """
+    Create the series section ``_index.md`` for a playlist or channel.
+
+    The section is transparent, so its posts also appear in the blog listing.
+    An existing ``_index.md`` is left untouched.
+
+    Args:
+        section_dir: Section folder under the content root
+        collection: Playlist or channel from ``fetch_youtube_collection``
+
+    Returns:
+        Path of the section's _index.md
+    """
+    index_path = section_dir / "_index.md"
+    if index_path.exists():
+        return index_path
+
+    section_dir.mkdir(parents=True, exist_ok=True)
+    description = collection["description"].strip()
+    front_matter = build_front_matter(
+        {
+            "title": collection["title"],
+            "description": description.split("\n")[0][:300] or None,
+            "sort_by": "date",
+            "paginate_by": 10,
+            "template": "blog.html",
+            "page_template": "page.html",
+            "transparent": True,
+        },
+        {
+            f"youtube_{collection['kind']}": collection["webpage_url"],
+            "youtube_uploader": collection["uploader"],
+        },
+    )
+    index_path.write_text(front_matter + (f"{description}\n" if description else ""), encoding="utf-8")
+    logger.info(f"📚 Series section created: {index_path}")
+    return index_path
+
+
+def parse_date_option(value: str) -> str:
+    """Validate a YYYY-MM-DD (or YYYYMMDD) command line date and return it as YYYYMMDD."""
+    digits = value.replace("-", "")
+    if not (len(digits) == 8 and digits.isdigit()):
+        raise argparse.ArgumentTypeError(f"expected a date like 2024-05-01, got {value!r}")
+    return digits
+
+
+async def process_youtube_collection(url: str, tts_backend: Optional[str] = None, voice: Optional[str] = None,
+                                     podcast: bool = False, after: Optional[str] = None,
+                                     before: Optional[str] = None, max_videos: Optional[int] = None) -> bool:
+    """
+    Import the videos of a YouTube playlist or channel into a series section.
+
+    Videos that already have a bundle (by ``source.id`` in asset.json) are
+    skipped, wherever the bundle lives.
+
+    Args:
+        url: Playlist or channel URL
+        tts_backend: Name of the TTS backend used for narration (settings default if None)
+        voice: Voice for the TTS backend (settings or backend default if None)
+        podcast: Also render podcast episodes
+        after: Only videos uploaded on or after this date (YYYYMMDD)
+        before: Only videos uploaded on or before this date (YYYYMMDD)
+        max_videos: Import at most this many new videos
+
+    Returns:
+        True if every attempted video was imported
+    """
+    collection = fetch_youtube_collection(url)
+    if not collection:
+        return False
+    entries = collection["entries"]
+    logger.info(f"📚 {collection['title']}: {len(entries)} video(s)")
+
+    section = make_slug(collection["title"]) or f"youtube-{collection['kind']}-{collection['id']}"
+    if (settings.content_root / section).exists() and not (settings.content_root / section / "_index.md").exists():
+        section += "-series"  # A post bundle already has this name
+    existing = imported_youtube_ids()
+    imported, failed = 0, []
+
+    for entry in entries:
+        if max_videos is not None and imported + len(failed) >= max_videos:
+            logger.info(f"⏹️ Reached the limit of {max_videos} new video(s)")
+            break
+        label = f"{entry['id']} ({entry['title'] or 'untitled'})"
+        if entry["id"] in existing:
+            logger.info(f"⏭️ Already imported: {label}")
+            continue
+
+        if after or before:
+            upload_date = entry["upload_date"]
+            if not upload_date:
+                # Flat listings often leave out dates; fetch this video's details to check
+                upload_date = (fetch_youtube_info(entry["url"]) or {}).get("upload_date")
+            if not upload_date:
+                logger.warning(f"⚠️ Unknown upload date, skipping: {label}")
+                continue
+            if after and upload_date < after:
+                if collection["kind"] == "channel":
+                    # Channel uploads are listed newest first, so the rest are older too
+                    logger.info(f"⏹️ Reached videos uploaded before {parse_upload_date(after)}")
+                    break
+                logger.info(f"⏭️ Uploaded {parse_upload_date(upload_date)}, before the range: {label}")
+                continue
+            if before and upload_date > before:
+                logger.info(f"⏭️ Uploaded {parse_upload_date(upload_date)}, after the range: {label}")
+                continue
+
+        if settings.dry_run:
+            logger.info(f"🔎 Would import: {label} -> {section}/")
+            imported += 1
+            continue
+
+        create_series_index(settings.content_root / section, collection)
+        logger.info(f"🎬 [{imported + len(failed) + 1}] {label}")
+        if await process_youtube_video(entry["url"], tts_backend, voice, podcast, section=section):
+            imported += 1
+            existing.add(entry["id"])
+        else:
+            failed.append(entry["id"])
+
+    logger.info(f"📚 Imported {imported} new video(s) into {section}/" + (f", {len(failed)} failed" if failed else ""))
+    return not failed
+
+
 def main():
     """Main entry point for YouTube to blog conversion."""
-    if len(sys.argv) < 2:
-        logger.error("❌ Usage: python youtube_to_blog.py <youtube_url>")
-        logger.info("Example: python youtube_to_blog.py https://www.youtube.com/watch?v=VIDEO_ID")
-        sys.exit(1)
-
-    url = sys.argv[1]
+    parser = argparse.ArgumentParser(description="Convert a YouTube video into a Zola blog post with AI narration")
+    parser.add_argument("url", help="YouTube video, playlist or channel URL, "
+                                    "e.g. https://www.youtube.com/watch?v=VIDEO_ID")
+    parser.add_argument("--tts-backend", choices=sorted(TTS_BACKENDS),
+                        help="TTS backend for narration (default: zolamac.toml, TTS_BACKEND env or 'edge')")
+    parser.add_argument("--voice", help="Voice name for the selected TTS backend")
+    parser.add_argument("--podcast", action="store_true",
+                        help="Also render the article as a two-host Gemini podcast episode (podcast.mp3)")
+    add_collection_arguments(parser)
+    add_cache_arguments(parser)
+    args = parser.parse_args()
+    configure_cache(args)
+
+    url = args.url
 
     # Validate URL format
     if not url.startswith(('http://', 'https://')):
@@ -465,17 +809,21 @@ def main():
         sys.exit(1)
 
     # Ensure required directories exist
-    BASE_CONTENT.mkdir(parents=True, exist_ok=True)
-    OUTPUT_FOLDER.mkdir(exist_ok=True)
+    settings.content_root.mkdir(parents=True, exist_ok=True)
+    settings.output_folder.mkdir(exist_ok=True)
 
     # Check for API keys
-    if not GROQ_API_KEY:
-        logger.warning("⚠️ GROQ_API_KEY not found. AI features will be limited.")
+    if not has_groq_keys(GROQ_API_KEY):
+        logger.warning("⚠️ GROQ_API_KEY (or GROQ_API_KEY_<n>) not found. AI features will be limited.")
     if not UNSPLASH_ACCESS_KEY:
         logger.warning("⚠️ UNSPLASH_ACCESS_KEY not found. Thumbnail generation may be limited.")
 
-    # Process the video
-    success = asyncio.run(process_youtube_video(url))
+    # Process the video (or every new video of a playlist or channel)
+    if is_youtube_collection_url(url):
+        success = asyncio.run(process_youtube_collection(url, args.tts_backend, args.voice, args.podcast,
+                                                         args.after, args.before, args.max_videos))
+    else:
+        success = asyncio.run(process_youtube_video(url, args.tts_backend, args.voice, args.podcast))
 
     if success:
         logger.info("🎉 YouTube video processing completed successfully!")
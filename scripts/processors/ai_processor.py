@@ -9,8 +9,99 @@ from typing import Optional, Dict, Any
 
 import requests
 
+from .cache import cache_key, file_digest, step_cache
+from .key_pool import APIKeyPool, status_code_from_error
+
 logger = logging.getLogger(__name__)
 
+# Client errors caused by the request itself, not by the key
+REQUEST_ERROR_STATUSES = {400, 413, 422}
+
+_groq_key_pools: Dict[str, APIKeyPool] = {}
+
+
+def get_groq_key_pool(groq_api_key: Optional[str] = None) -> APIKeyPool:
+    """
+    Get the Groq key pool (GROQ_API_KEY / GROQ_API_KEY_<n>), including a key passed by the caller.
+
+    Args:
+        groq_api_key: Key passed explicitly by the caller
+
+    Returns:
+        Shared APIKeyPool for Groq
+    """
+    pool_id = groq_api_key or ""
+    if pool_id not in _groq_key_pools:
+        _groq_key_pools[pool_id] = APIKeyPool.from_env("groq", "GROQ_API_KEY", extra_keys=[groq_api_key])
+    return _groq_key_pools[pool_id]
+
+
+def has_groq_keys(groq_api_key: Optional[str] = None) -> bool:
+    """Whether any Groq key is configured (GROQ_API_KEY, GROQ_API_KEY_<n> or passed by the caller)."""
+    return len(get_groq_key_pool(groq_api_key)) > 0
+
+
+def groq_post(url: str, groq_api_key: str, headers: Optional[Dict[str, str]] = None, **kwargs: Any) -> requests.Response:
+    """
+    POST to the Groq API using the healthiest key in the Groq key pool.
+
+    Rate limits (429 with Retry-After) and key errors are recorded so the next
+    call picks another key; the exception is re-raised for the caller's retry loop.
+
+    Args:
+        url: Groq endpoint
+        groq_api_key: Fallback key if the pool is empty
+        headers: Extra headers (Authorization is set here)
+        **kwargs: Passed through to requests.post
+
+    Returns:
+        Successful response
+    """
+    pool = get_groq_key_pool(groq_api_key)
+    api_key = pool.acquire() or groq_api_key
+    request_headers = dict(headers or {})
+    request_headers["Authorization"] = f"Bearer {api_key}"
+
+    try:
+        resp = requests.post(url, headers=request_headers, **kwargs)
+        resp.raise_for_status()
+    except requests.RequestException as e:
+        if status_code_from_error(e) not in REQUEST_ERROR_STATUSES:
+            pool.report_error(api_key, e)
+        raise
+
+    pool.report_success(api_key)
+    return resp
+
+
+def groq_post_json(url: str, groq_api_key: str, step: str, headers: Optional[Dict[str, str]] = None,
+                   cache_inputs: Any = None, **kwargs: Any) -> Dict[str, Any]:
+    """
+    POST to the Groq API and return the JSON body, cached by the request inputs.
+
+    The cache key is the endpoint plus the JSON payload (model, prompt and
+    sampling parameters), so changing a prompt or model only redoes that step.
+
+    Args:
+        url: Groq endpoint
+        groq_api_key: Fallback key if the pool is empty
+        step: Cache step name (see ``cache.CACHE_STEPS``)
+        headers: Extra headers
+        cache_inputs: Inputs to hash instead of the payload (e.g. for file uploads)
+        **kwargs: Passed through to ``groq_post``
+
+    Returns:
+        Parsed JSON response
+    """
+    key = cache_key(url, cache_inputs if cache_inputs is not None else (kwargs.get("json"), kwargs.get("data")))
+    cached = step_cache.get(step, key)
+    if cached is not None:
+        return cached
+
+    data = groq_post(url, groq_api_key, headers=headers, **kwargs).json()
+    step_cache.put(step, key, data)
+    return data
+
 
 def summarize_text_with_groq(text: str, groq_api_key: str, summary_ratio: float = 0.2) -> str:
     """
@@ -24,16 +115,14 @@ def summarize_text_with_groq(text: str, groq_api_key: str, summary_ratio: float
     Returns:
         Summarized text or original text if API fails
     """
-    if not groq_api_key:
+    if not has_groq_keys(groq_api_key):
         logger.warning("GROQ_API_KEY not set. Skipping GROQ summarization.")
         return text
 
     try:
-        headers = {"Authorization": f"Bearer {groq_api_key}"}
         data = {"text": text, "summary_ratio": summary_ratio}
-        resp = requests.post("https://api.groq.ai/v1/summarize", headers=headers, json=data, timeout=60)
-        resp.raise_for_status()
-        summary = resp.json().get("summary")
+        result = groq_post_json("https://api.groq.ai/v1/summarize", groq_api_key, "summarize", json=data, timeout=60)
+        summary = result.get("summary")
         return summary or text
     except Exception as e:
         logger.error(f"GROQ summarization failed: {e}. Using original text as summary.")
@@ -53,7 +142,7 @@ def generate_ai_summary_and_structure(title: str, summary: str, transcript_text:
     Returns:
         Structured summary text or None if failed
     """
-    if not groq_api_key:
+    if not has_groq_keys(groq_api_key):
         logger.warning("GROQ_API_KEY not set. Skipping AI structure generation.")
         return None
 
@@ -82,7 +171,6 @@ def generate_ai_summary_and_structure(title: str, summary: str, transcript_text:
     """
 
     headers = {
-        "Authorization": f"Bearer {groq_api_key}",
         "Content-Type": "application/json",
     }
 
@@ -102,9 +190,8 @@ def generate_ai_summary_and_structure(title: str, summary: str, transcript_text:
 
     for attempt in range(3):  # Retry up to 3 times
         try:
-            resp = requests.post("https://api.groq.com/openai/v1/chat/completions", headers=headers, json=payload, timeout=90)
-            resp.raise_for_status()
-            data = resp.json()
+            data = groq_post_json("https://api.groq.com/openai/v1/chat/completions", groq_api_key, "structure",
+                                  headers=headers, json=payload, timeout=90)
             content = data["choices"][0]["message"]["content"].strip()
             logger.info("✅ AI structure generated successfully")
             return content
@@ -132,7 +219,7 @@ def generate_final_article(title: str, summary: str, transcript_text: str, ai_st
     Returns:
         Human-readable article text or None if failed
     """
-    if not groq_api_key:
+    if not has_groq_keys(groq_api_key):
         logger.warning("GROQ_API_KEY not set. Skipping final article generation.")
         return None
 
@@ -165,7 +252,6 @@ def generate_final_article(title: str, summary: str, transcript_text: str, ai_st
     """
 
     headers = {
-        "Authorization": f"Bearer {groq_api_key}",
         "Content-Type": "application/json",
     }
 
@@ -185,9 +271,8 @@ def generate_final_article(title: str, summary: str, transcript_text: str, ai_st
 
     for attempt in range(3):  # Retry up to 3 times
         try:
-            resp = requests.post("https://api.groq.com/openai/v1/chat/completions", headers=headers, json=payload, timeout=90)
-            resp.raise_for_status()
-            data = resp.json()
+            data = groq_post_json("https://api.groq.com/openai/v1/chat/completions", groq_api_key, "article",
+                                  headers=headers, json=payload, timeout=90)
             content = data["choices"][0]["message"]["content"].strip()
             logger.info("✅ Final article generated successfully")
             return content
@@ -201,6 +286,91 @@ def generate_final_article(title: str, summary: str, transcript_text: str, ai_st
     return None
 
 
+def generate_podcast_script(title: str, article: str, groq_api_key: str,
+                            speakers: tuple = ("Host", "Guest")) -> Optional[str]:
+    """
+    Rewrite an article as a two-host podcast conversation.
+
+    Every line of the result is a ``Speaker: text`` turn using the given
+    speaker labels, ready for Gemini multi-speaker TTS.
+
+    Args:
+        title: Content title
+        article: Article text (e.g. output of generate_final_article)
+        groq_api_key: Groq API key
+        speakers: The two speaker labels to use
+
+    Returns:
+        Dialogue script or None if failed
+    """
+    if not has_groq_keys(groq_api_key):
+        logger.warning("GROQ_API_KEY not set. Skipping podcast script generation.")
+        return None
+
+    logger.info("🎙️ Generating two-host podcast script via Groq...")
+
+    host, guest = speakers
+    prompt = f"""
+    You are a podcast producer. Turn the following article into a lively, natural conversation between two hosts named "{host}" and "{guest}".
+
+    Requirements:
+    - {host} introduces the topic and guides the conversation; {guest} explains details and adds insights.
+    - Every line MUST start with "{host}:" or "{guest}:" followed by what they say. No other lines, headings, stage directions or markdown.
+    - Cover all the key points of the article, in order.
+    - Keep each turn to 1-3 sentences. Total length: 400-700 words.
+    - Write in the same language as the article.
+
+    Episode Title: {title}
+
+    Article:
+    {article[:10000]}
+
+    Write the conversation now:
+    """
+
+    headers = {
+        "Content-Type": "application/json",
+    }
+
+    payload = {
+        "model": "llama-3.3-70b-versatile",
+        "messages": [
+            {
+                "role": "system",
+                "content": "You are a podcast scriptwriter who writes engaging two-person dialogues in a strict 'Speaker: line' format."
+            },
+            {"role": "user", "content": prompt},
+        ],
+        "temperature": 0.7,
+        "max_tokens": 2500,
+        "top_p": 0.9,
+    }
+
+    for attempt in range(3):  # Retry up to 3 times
+        try:
+            data = groq_post_json("https://api.groq.com/openai/v1/chat/completions", groq_api_key, "podcast_script",
+                                  headers=headers, json=payload, timeout=90)
+            content = data["choices"][0]["message"]["content"].strip()
+
+            # Drop anything that isn't a speaker turn (stray headings, notes)
+            lines = [line.strip() for line in content.splitlines()
+                     if line.strip().startswith((f"{host}:", f"{guest}:"))]
+            if not lines:
+                logger.warning("⚠️ Podcast script had no speaker turns")
+                return None
+
+            logger.info("✅ Podcast script generated successfully")
+            return "\n".join(lines)
+        except requests.RequestException as e:
+            logger.warning(f"Groq API (Podcast) attempt {attempt + 1}/3 failed: {e}")
+            if attempt < 2:
+                import time
+                time.sleep(2)  # Wait before retry
+
+    logger.error("❌ Groq API (Podcast) failed after all retries")
+    return None
+
+
 def generate_social_media_post(title: str, summary: str, tags: list, groq_api_key: str) -> Optional[str]:
     """
     Generate a concise, promotional post for social media.
@@ -214,7 +384,7 @@ def generate_social_media_post(title: str, summary: str, tags: list, groq_api_ke
     Returns:
         Social media post text or None if failed
     """
-    if not groq_api_key:
+    if not has_groq_keys(groq_api_key):
         logger.warning("GROQ_API_KEY not set. Skipping social media post generation.")
         return None
 
@@ -240,7 +410,6 @@ def generate_social_media_post(title: str, summary: str, tags: list, groq_api_ke
     """
 
     headers = {
-        "Authorization": f"Bearer {groq_api_key}",
         "Content-Type": "application/json",
     }
 
@@ -260,9 +429,8 @@ def generate_social_media_post(title: str, summary: str, tags: list, groq_api_ke
 
     for attempt in range(3):  # Retry up to 3 times
         try:
-            resp = requests.post("https://api.groq.com/openai/v1/chat/completions", headers=headers, json=payload, timeout=45)
-            resp.raise_for_status()
-            data = resp.json()
+            data = groq_post_json("https://api.groq.com/openai/v1/chat/completions", groq_api_key, "social",
+                                  headers=headers, json=payload, timeout=45)
             content = data["choices"][0]["message"]["content"].strip()
 
             # Simple check to enforce the limit if the model went slightly over
@@ -294,7 +462,7 @@ def transcribe_audio_with_groq(audio_filepath: str, groq_api_key: str, model: st
     Returns:
         Transcribed text
     """
-    if not groq_api_key:
+    if not has_groq_keys(groq_api_key):
         logger.error("GROQ_API_KEY not set. Cannot transcribe audio.")
         return "Automatic transcription unavailable due to missing API key."
 
@@ -316,7 +484,6 @@ def transcribe_audio_with_groq(audio_filepath: str, groq_api_key: str, model: st
 
 def _transcribe_single_file(audio_filepath: str, groq_api_key: str, model: str) -> str:
     """Transcribe a single audio file."""
-    headers = {"Authorization": f"Bearer {groq_api_key}"}
     data = {'model': model}
 
     for attempt in range(3):  # Retry up to 3 times
@@ -326,16 +493,16 @@ def _transcribe_single_file(audio_filepath: str, groq_api_key: str, model: str)
                     'file': (audio_filepath.split('/')[-1], audio_file, 'audio/mpeg')
                 }
 
-                resp = requests.post(
+                result = groq_post_json(
                     "https://api.groq.com/openai/v1/audio/transcriptions",
-                    headers=headers,
+                    groq_api_key,
+                    "transcribe",
+                    cache_inputs=(model, file_digest(Path(audio_filepath))),
                     data=data,
                     files=files,
                     timeout=120
                 )
 
-            resp.raise_for_status()
-            result = resp.json()
             return result.get("text", "")
 
         except requests.HTTPError as e:
@@ -0,0 +1,189 @@
+"""
+Audio Metadata Module
+Writes ID3v2 tags, cover art and chapter markers to generated MP3 files.
+"""
+
+import logging
+import re
+from pathlib import Path
+from typing import List, Optional, Tuple
+
+from mutagen.id3 import (
+    ID3, ID3NoHeaderError, APIC, CHAP, COMM, CTOC, CTOCFlags, TALB, TCON, TDRC, TIT2, TPE1
+)
+
+from .front_matter import read_front_matter, get_authors
+from .tts_engine import get_audio_duration
+
+logger = logging.getLogger(__name__)
+
+ALBUM_NAME = "Zola-mac"
+BUNDLE_AUDIO_FILES = ["asset.mp3", "article.mp3", "narration.mp3", "podcast.mp3"]
+MARKDOWN_HEADING = re.compile(r"^(#{1,4})\s+(.+?)\s*#*\s*$", re.MULTILINE)
+
+
+def estimate_chapter_times(text: str, headings: List[Tuple[str, str]], duration: float) -> List[Tuple[float, str]]:
+    """
+    Estimate chapter start times from where each heading appears in the narrated text.
+
+    Speech rate is roughly constant, so a heading's character offset divided
+    by the text length, scaled to the audio's duration, approximates where
+    its section starts. Headings that do not appear in the text (e.g. ones a
+    summary dropped) get no chapter.
+
+    Args:
+        text: Exactly the text that was narrated (the summary, if a summary was read)
+        headings: List of (tag, heading) tuples, in document order
+        duration: Audio duration in seconds
+
+    Returns:
+        List of (start_seconds, title) tuples; the first chapter starts at 0
+    """
+    if not text or not headings or duration <= 0:
+        return []
+
+    chapters = []
+    search_from = 0
+    for _, heading in headings:
+        offset = text.find(heading, search_from)
+        if offset == -1:
+            continue
+        search_from = offset + len(heading)
+        start = duration * offset / len(text)
+        if chapters and start - chapters[-1][0] < 1.0:
+            continue  # Merge headings that would produce near-empty chapters
+        chapters.append((start, heading))
+
+    if chapters:
+        chapters[0] = (0.0, chapters[0][1])
+    return chapters
+
+
+def markdown_headings(markdown: str) -> List[Tuple[str, str]]:
+    """Headings of a Markdown text as ``(tag, heading)`` tuples for ``estimate_chapter_times``."""
+    return [(f"h{len(match.group(1))}", match.group(2)) for match in MARKDOWN_HEADING.finditer(markdown or "")]
+
+
+def _add_chapters(tags: ID3, chapters: List[Tuple[float, str]], duration: float) -> None:
+    """Add CHAP frames and a top-level CTOC frame for the given chapters."""
+    tags.delall("CHAP")
+    tags.delall("CTOC")
+
+    element_ids = []
+    for index, (start, title) in enumerate(chapters):
+        end = chapters[index + 1][0] if index + 1 < len(chapters) else duration
+        element_id = f"chp{index}"
+        element_ids.append(element_id)
+        tags.add(CHAP(
+            element_id=element_id,
+            start_time=int(start * 1000),
+            end_time=int(end * 1000),
+            sub_frames=[TIT2(encoding=3, text=[title])],
+        ))
+
+    tags.add(CTOC(
+        element_id="toc",
+        flags=CTOCFlags.TOP_LEVEL | CTOCFlags.ORDERED,
+        child_element_ids=element_ids,
+        sub_frames=[TIT2(encoding=3, text=["Chapters"])],
+    ))
+
+
+def tag_audio_file(mp3_path: Path, title: str, date: Optional[str] = None, author: Optional[str] = None,
+                   cover_path: Optional[Path] = None, chapters: Optional[List[Tuple[float, str]]] = None,
+                   comment: Optional[str] = None) -> bool:
+    """
+    Write ID3v2 tags to an MP3 file.
+
+    Args:
+        mp3_path: MP3 file to tag
+        title: Episode/post title
+        date: Publication date (YYYY-MM-DD or ISO timestamp)
+        author: Artist/author name
+        cover_path: JPEG to embed as front cover (e.g. asset.jpg)
+        chapters: List of (start_seconds, title) chapter markers
+        comment: Optional comment (e.g. source URL)
+
+    Returns:
+        True if tags were written, False otherwise
+    """
+    if not mp3_path.exists() or mp3_path.stat().st_size == 0:
+        logger.warning(f"⚠️ Cannot tag missing or empty audio file: {mp3_path}")
+        return False
+
+    try:
+        try:
+            tags = ID3(mp3_path)
+        except ID3NoHeaderError:
+            tags = ID3()
+
+        tags.setall("TIT2", [TIT2(encoding=3, text=[title])])
+        tags.setall("TALB", [TALB(encoding=3, text=[ALBUM_NAME])])
+        tags.setall("TCON", [TCON(encoding=3, text=["Podcast"])])
+        if author:
+            tags.setall("TPE1", [TPE1(encoding=3, text=[author])])
+        if date:
+            tags.setall("TDRC", [TDRC(encoding=3, text=[str(date)[:10]])])
+        if comment:
+            tags.setall("COMM", [COMM(encoding=3, lang="eng", desc="", text=[comment])])
+
+        if cover_path and cover_path.exists():
+            mime = "image/png" if cover_path.suffix.lower() == ".png" else "image/jpeg"
+            tags.setall("APIC", [APIC(
+                encoding=3, mime=mime, type=3, desc="Cover", data=cover_path.read_bytes()
+            )])
+
+        if chapters:
+            _add_chapters(tags, chapters, get_audio_duration(mp3_path))
+
+        tags.save(mp3_path, v2_version=3)
+        logger.info(f"✅ ID3 tags written to {mp3_path.name}"
+                    + (f" ({len(chapters)} chapters)" if chapters else ""))
+        return True
+
+    except Exception as e:
+        logger.error(f"❌ Failed to write ID3 tags to {mp3_path}: {e}")
+        return False
+
+
+def tag_bundle_audio(post_dir: Path, chapters: Optional[List[Tuple[float, str]]] = None,
+                     audio_files: Optional[List[str]] = None,
+                     outline: Optional[Tuple[str, List[Tuple[str, str]]]] = None,
+                     author: Optional[str] = None, source_url: Optional[str] = None) -> int:
+    """
+    Tag every generated MP3 in a page bundle from its front matter.
+
+    Title, date and authors are read from index.md, so hand edits to the
+    front matter end up in the tags.
+
+    Args:
+        post_dir: Page bundle directory
+        chapters: Chapter markers to add to the tagged files (e.g. a video's own chapters)
+        audio_files: File names to tag (defaults to BUNDLE_AUDIO_FILES)
+        outline: ``(narrated text, headings)``; chapters are estimated from
+            it for each file's duration (see ``estimate_chapter_times``)
+        author: Artist if the front matter names no authors or source site
+        source_url: Comment if the front matter has no source URL
+
+    Returns:
+        Number of files tagged
+    """
+    front_matter = read_front_matter(post_dir / "index.md")
+    extra = front_matter.get("extra", {})
+    title = str(front_matter.get("title") or post_dir.name)
+    date = front_matter.get("date")
+    artist = ", ".join(get_authors(front_matter)) or extra.get("source_site") or author
+    source_url = extra.get("youtube_url") or extra.get("canonical_url") or source_url
+
+    tagged = 0
+    for file_name in audio_files or BUNDLE_AUDIO_FILES:
+        mp3_path = post_dir / file_name
+        if not mp3_path.exists():
+            continue
+        file_chapters = chapters
+        if not file_chapters and outline:
+            file_chapters = estimate_chapter_times(outline[0], outline[1], get_audio_duration(mp3_path))
+        if tag_audio_file(mp3_path, title, str(date) if date else None, artist,
+                          post_dir / "asset.jpg", file_chapters, source_url):
+            tagged += 1
+    return tagged
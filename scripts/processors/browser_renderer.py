@@ -0,0 +1,81 @@
+"""
+Browser Renderer Module
+Renders JavaScript-only pages (SPAs, X.com, some Medium variants) in a local
+headless Chromium via Playwright, for when the static HTML is an empty shell.
+"""
+
+import logging
+from concurrent.futures import ThreadPoolExecutor
+from typing import Optional
+
+from .config import settings
+from .http_fetcher import RobotsDisallowed, fetcher
+
+logger = logging.getLogger(__name__)
+
+# Resources the article text never depends on; skipping them makes rendering much faster
+BLOCKED_RESOURCE_TYPES = {"image", "media", "font"}
+
+
+def browser_available() -> bool:
+    """Check whether Playwright is installed."""
+    try:
+        import playwright.sync_api  # noqa: F401
+        return True
+    except ImportError:
+        return False
+
+
+def _render(url: str) -> str:
+    from playwright.sync_api import sync_playwright, TimeoutError as PlaywrightTimeoutError
+
+    timeout_ms = settings.render_timeout * 1000
+    with sync_playwright() as p:
+        browser = p.chromium.launch(headless=True)
+        try:
+            context = browser.new_context(user_agent=settings.fetch_user_agent, java_script_enabled=True)
+            page = context.new_page()
+            page.route("**/*", lambda route: route.abort()
+                       if route.request.resource_type in BLOCKED_RESOURCE_TYPES else route.continue_())
+            try:
+                page.goto(url, wait_until=settings.render_wait_until, timeout=timeout_ms)
+            except PlaywrightTimeoutError:
+                # Pages with endless polling never go idle; whatever has rendered by now is usually enough
+                logger.warning(f"⚠️ Page did not finish loading within {settings.render_timeout:.0f}s, "
+                               f"using what has rendered")
+            return page.content()
+        finally:
+            browser.close()
+
+
+def render_page(url: str) -> Optional[str]:
+    """
+    Load a page in headless Chromium and return the DOM after scripts ran.
+
+    The request counts against the host's limits in the shared fetcher
+    (robots.txt, ``fetch.delay``, ``fetch.max_per_host``) and uses the same
+    User-Agent.
+
+    Args:
+        url: Page URL
+
+    Returns:
+        Rendered HTML, or None if Playwright is not installed or rendering fails
+    """
+    if not browser_available():
+        logger.warning("⚠️ Headless rendering needs Playwright: "
+                       "pip install playwright && playwright install chromium")
+        return None
+
+    logger.info(f"🌐 Rendering page in headless Chromium: {url}")
+    try:
+        with fetcher.slot(url):
+            # The sync API refuses to run inside an asyncio event loop, which
+            # is where the pipelines call us from, so render on a worker thread
+            with ThreadPoolExecutor(max_workers=1) as pool:
+                return pool.submit(_render, url).result()
+    except RobotsDisallowed:
+        raise
+    except Exception as e:
+        logger.warning(f"⚠️ Headless rendering failed for {url}: {e}")
+        return None
@@ -0,0 +1,364 @@
+"""
+Bundle Schema Module
+Defines the versioned asset.json schema shared by every pipeline, plus
+validation and migration of older bundle metadata.
+"""
+
+import hashlib
+import json
+import logging
+from datetime import datetime, timezone
+from pathlib import Path
+from typing import Any, Dict, List, Optional
+
+from .tts_engine import get_audio_duration
+
+logger = logging.getLogger(__name__)
+
+SCHEMA_VERSION = 1
+METADATA_FILE = "asset.json"
+
+SOURCE_TYPES = ["web", "youtube", "text", "unknown"]
+STEP_STATUSES = ["ok", "failed", "skipped"]
+AUDIO_SUFFIXES = {".mp3", ".wav", ".m4a", ".ogg"}
+
+# Files that are edited after generation and therefore not checksummed
+UNTRACKED_FILES = {METADATA_FILE, "index.md"}
+
+# Top-level keys and their expected types
+TOP_LEVEL_FIELDS = {
+    "schema_version": int,
+    "slug": str,
+    "title": str,
+    "lang": (str, type(None)),
+    "source": dict,
+    "created_at": str,
+    "updated_at": str,
+    "pipeline": list,
+    "providers": dict,
+    "audio": dict,
+    "files": dict,
+    "content": dict,
+}
+
+# Keys of the legacy AI-enrichment shape that are kept under "content"
+LEGACY_CONTENT_KEYS = [
+    "tags", "keywords", "excerpt", "category", "read_time_minutes", "difficulty", "tl_dr",
+    "key_takeaways", "related_topics", "suggested_cta", "diagnostics", "translations",
+    "narration_word_count",
+]
+
+
+def now_iso() -> str:
+    """Current UTC time as an ISO 8601 timestamp."""
+    return datetime.now(timezone.utc).replace(microsecond=0).isoformat()
+
+
+def file_checksum(path: Path) -> str:
+    """SHA-256 of a file's contents."""
+    digest = hashlib.sha256()
+    with open(path, "rb") as f:
+        for block in iter(lambda: f.read(65536), b""):
+            digest.update(block)
+    return digest.hexdigest()
+
+
+def new_bundle_metadata(slug: str, title: str, source_type: str, url: Optional[str] = None,
+                        lang: Optional[str] = None, **source: Any) -> Dict[str, Any]:
+    """
+    Create an empty metadata document for a bundle.
+
+    Args:
+        slug: Bundle folder name
+        title: Post title
+        source_type: One of SOURCE_TYPES
+        url: Source URL
+        lang: Content language code
+        **source: Additional source fields (e.g. ``id``, ``author``, ``published``);
+            None values are left out
+
+    Returns:
+        Metadata dictionary following the current schema
+    """
+    timestamp = now_iso()
+    return {
+        "schema_version": SCHEMA_VERSION,
+        "slug": slug,
+        "title": title,
+        "lang": lang,
+        "source": {"type": source_type, "url": url, **{k: v for k, v in source.items() if v is not None}},
+        "created_at": timestamp,
+        "updated_at": timestamp,
+        "pipeline": [],
+        "providers": {},
+        "audio": {},
+        "files": {},
+        "content": {},
+    }
+
+
+def record_step(metadata: Dict[str, Any], name: str, status: str = "ok", provider: Optional[str] = None,
+                **detail: Any) -> None:
+    """
+    Append a pipeline step to the metadata.
+
+    Args:
+        metadata: Bundle metadata
+        name: Step name (e.g. ``scrape``, ``summarize``, ``tts``)
+        status: One of STEP_STATUSES
+        provider: Service or backend that performed the step
+        **detail: Extra step details (e.g. ``voice``, ``error``)
+    """
+    step = {"name": name, "status": status, "timestamp": now_iso()}
+    if provider:
+        step["provider"] = provider
+        metadata["providers"][name] = provider
+    step.update({k: v for k, v in detail.items() if v is not None})
+    metadata["pipeline"].append(step)
+
+
+def collect_files(metadata: Dict[str, Any], post_dir: Path) -> None:
+    """
+    Record size and checksum of every bundle file, and duration of audio files.
+
+    Args:
+        metadata: Bundle metadata (``files`` and ``audio`` are replaced)
+        post_dir: Page bundle directory
+    """
+    files = {}
+    audio = {}
+    for path in sorted(post_dir.iterdir()):
+        if not path.is_file() or path.name in UNTRACKED_FILES or path.name.startswith("."):
+            continue
+        entry = {"bytes": path.stat().st_size, "sha256": file_checksum(path)}
+        files[path.name] = entry
+        if path.suffix.lower() in AUDIO_SUFFIXES:
+            previous = metadata.get("audio", {}).get(path.name, {})
+            if previous.get("sha256") == entry["sha256"] and previous.get("duration"):
+                duration = previous["duration"]  # Unchanged file, skip ffprobe
+            else:
+                duration = round(get_audio_duration(path), 2)
+            audio[path.name] = {"duration": duration, **entry}
+    metadata["files"] = files
+    metadata["audio"] = audio
+
+
+def save_bundle_metadata(metadata: Dict[str, Any], post_dir: Path) -> Path:
+    """
+    Refresh file records and write asset.json for a bundle.
+
+    Args:
+        metadata: Bundle metadata
+        post_dir: Page bundle directory
+
+    Returns:
+        Path of the written asset.json
+    """
+    metadata["updated_at"] = now_iso()
+    collect_files(metadata, post_dir)
+
+    for error in validate_metadata(metadata):
+        logger.warning(f"⚠️ {post_dir.name}: {error}")
+
+    json_path = post_dir / METADATA_FILE
+    with open(json_path, "w", encoding="utf-8") as f:
+        json.dump(metadata, f, indent=2, ensure_ascii=False)
+    logger.info(f"✅ Metadata saved: {json_path}")
+    return json_path
+
+
+def load_bundle_metadata(post_dir: Path) -> Optional[Dict[str, Any]]:
+    """
+    Load a bundle's asset.json, migrating older shapes in memory.
+
+    Args:
+        post_dir: Page bundle directory
+
+    Returns:
+        Metadata following the current schema, or None if there is none
+    """
+    json_path = post_dir / METADATA_FILE
+    if not json_path.exists():
+        return None
+    with open(json_path, "r", encoding="utf-8") as f:
+        data = json.load(f)
+    if data.get("schema_version") != SCHEMA_VERSION:
+        data = migrate_metadata(data, post_dir)
+    return data
+
+
+def load_bundle_source(post_dir: Path) -> Optional[Dict[str, Any]]:
+    """
+    Read only the source of a bundle's asset.json, in any schema version.
+
+    Unlike ``load_bundle_metadata`` this never migrates, so it does not
+    checksum or probe the bundle's files; use it to scan many bundles.
+
+    Args:
+        post_dir: Page bundle directory
+
+    Returns:
+        ``source`` in the current shape (``type``, ``url``, ``id``), or None
+        if there is no asset.json
+    """
+    json_path = post_dir / METADATA_FILE
+    if not json_path.exists():
+        return None
+    with open(json_path, "r", encoding="utf-8") as f:
+        data = json.load(f)
+    if isinstance(data.get("source"), dict):
+        return data["source"]
+    # Legacy shapes, as mapped by migrate_metadata
+    if "video_id" in data:
+        return {"type": "youtube", "url": data.get("url"), "id": data["video_id"]}
+    return {"type": "web" if data.get("url") else "unknown", "url": data.get("url")}
+
+
+def validate_metadata(data: Dict[str, Any]) -> List[str]:
+    """
+    Check metadata against the current schema.
+
+    Args:
+        data: Parsed asset.json
+
+    Returns:
+        List of problems (empty if the document conforms)
+    """
+    errors = []
+    if data.get("schema_version") != SCHEMA_VERSION:
+        errors.append(f"schema_version is {data.get('schema_version')!r}, expected {SCHEMA_VERSION}")
+
+    for key, expected in TOP_LEVEL_FIELDS.items():
+        if key not in data:
+            errors.append(f"missing '{key}'")
+        elif not isinstance(data[key], expected):
+            errors.append(f"'{key}' has type {type(data[key]).__name__}")
+
+    unknown = set(data) - set(TOP_LEVEL_FIELDS)
+    if unknown:
+        errors.append(f"unknown keys: {', '.join(sorted(unknown))}")
+
+    source = data.get("source")
+    if isinstance(source, dict) and source.get("type") not in SOURCE_TYPES:
+        errors.append(f"source.type {source.get('type')!r} is not one of {', '.join(SOURCE_TYPES)}")
+
+    for key in ("created_at", "updated_at"):
+        value = data.get(key)
+        if isinstance(value, str):
+            try:
+                datetime.fromisoformat(value)
+            except ValueError:
+                errors.append(f"'{key}' is not an ISO 8601 timestamp: {value!r}")
+
+    for index, step in enumerate(data.get("pipeline") or []):
+        if not isinstance(step, dict) or not step.get("name"):
+            errors.append(f"pipeline[{index}] has no name")
+        elif step.get("status") not in STEP_STATUSES:
+            errors.append(f"pipeline[{index}] ({step['name']}) has invalid status {step.get('status')!r}")
+
+    for section in ("audio", "files"):
+        for name, entry in (data.get(section) or {}).items():
+            if not isinstance(entry, dict) or "bytes" not in entry or "sha256" not in entry:
+                errors.append(f"{section}.{name} needs 'bytes' and 'sha256'")
+            elif section == "audio" and "duration" not in entry:
+                errors.append(f"audio.{name} has no duration")
+
+    return errors
+
+
+def verify_files(data: Dict[str, Any], post_dir: Path) -> List[str]:
+    """
+    Compare recorded file checksums with the files on disk.
+
+    Args:
+        data: Bundle metadata following the current schema
+        post_dir: Page bundle directory
+
+    Returns:
+        List of missing or modified files
+    """
+    problems = []
+    for name, entry in (data.get("files") or {}).items():
+        path = post_dir / name
+        if not path.exists():
+            problems.append(f"{name} is recorded but missing")
+        elif path.stat().st_size != entry.get("bytes") or file_checksum(path) != entry.get("sha256"):
+            problems.append(f"{name} changed since asset.json was written")
+    return problems
+
+
+def _timestamp_from(value: Any, post_dir: Path) -> str:
+    """Use an existing timestamp if it is one, otherwise the asset.json mtime."""
+    if isinstance(value, str):
+        try:
+            return datetime.fromisoformat(value).isoformat()
+        except ValueError:
+            pass  # Older pipelines stored str(Path.cwd()) here
+    json_path = post_dir / METADATA_FILE
+    mtime = json_path.stat().st_mtime if json_path.exists() else datetime.now(timezone.utc).timestamp()
+    return datetime.fromtimestamp(mtime, timezone.utc).replace(microsecond=0).isoformat()
+
+
+def migrate_metadata(data: Dict[str, Any], post_dir: Path) -> Dict[str, Any]:
+    """
+    Convert any earlier asset.json shape to the current schema.
+
+    Handles the web pipeline (``url``/``chunks``/``mp3`` and the later
+    ``pub_date``/``processing_date`` form), the YouTube pipeline
+    (``video_id``/``uploader``) and the AI-enrichment shape
+    (``tl_dr``/``diagnostics``/``translations``).
+
+    Args:
+        data: Parsed asset.json in any known shape
+        post_dir: Page bundle directory
+
+    Returns:
+        Metadata following the current schema
+    """
+    if data.get("schema_version") == SCHEMA_VERSION:
+        return data
+
+    legacy = dict(data)
+    slug = legacy.pop("slug", None) or post_dir.name
+    title = legacy.pop("title", None) or slug
+    url = legacy.pop("url", None)
+
+    if "video_id" in legacy:
+        source_type = "youtube"
+    elif url:
+        source_type = "web"
+    elif any(key in legacy for key in LEGACY_CONTENT_KEYS):
+        source_type = "text"
+    else:
+        source_type = "unknown"
+
+    source = {}
+    for old_key, new_key in (("video_id", "id"), ("uploader", "author"), ("upload_date", "published"),
+                             ("pub_date", "published"), ("duration", "duration"), ("view_count", "view_count")):
+        if old_key in legacy:
+            source[new_key] = legacy.pop(old_key)
+
+    # yt-dlp upload dates are YYYYMMDD
+    published = source.get("published")
+    if isinstance(published, str) and len(published) == 8 and published.isdigit():
+        source["published"] = f"{published[:4]}-{published[4:6]}-{published[6:]}"
+
+    migrated = new_bundle_metadata(slug, title, source_type, url, legacy.pop("lang", None), **source)
+    migrated["created_at"] = _timestamp_from(legacy.pop("processing_date", None), post_dir)
+
+    for provider_key, step in (("tts_backend", "tts"), ("voice", "voice")):
+        if legacy.get(provider_key):
+            migrated["providers"][step] = legacy[provider_key]
+        legacy.pop(provider_key, None)
+
+    # File paths are derived from the bundle contents now
+    for key in ("mp3", "text", "audio_file", "text_file", "markdown_file", "thumbnail_file", "captions_file",
+                "podcast_file", "podcast_episode"):
+        legacy.pop(key, None)
+
+    # Everything else is descriptive content
+    migrated["content"].update(legacy)
+
+    collect_files(migrated, post_dir)
+    migrated["updated_at"] = now_iso()
+    return migrated
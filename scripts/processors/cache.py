@@ -0,0 +1,173 @@
+"""
+Step Cache Module
+Content-addressed cache for pipeline steps (scraping, AI calls, thumbnails,
+downloads and TTS), keyed by a hash of each step's inputs.
+"""
+
+import argparse
+import hashlib
+import json
+import logging
+import os
+import shutil
+from pathlib import Path
+from typing import Any, Callable, Dict, Iterable, Optional
+
+logger = logging.getLogger(__name__)
+
+CACHE_DIR = Path(os.getenv("ZOLAMAC_CACHE_DIR", ".cache/zolamac"))
+VALUE_FILE = "value.json"
+
+# Steps that can be refreshed individually with --refresh
+CACHE_STEPS = [
+    "scrape", "summarize", "structure", "article", "social", "podcast_script",
+    "keywords", "transcribe", "thumbnail", "download", "tts", "fetch",
+]
+
+
+def cache_key(*parts: Any) -> str:
+    """
+    Hash a step's inputs into a cache key.
+
+    Args:
+        *parts: JSON-serializable inputs (text, prompts, model names, voices...)
+
+    Returns:
+        Hex SHA-256 digest
+    """
+    encoded = json.dumps(parts, sort_keys=True, ensure_ascii=False, default=str)
+    return hashlib.sha256(encoded.encode("utf-8")).hexdigest()
+
+
+def file_digest(path: Path) -> str:
+    """SHA-256 of a file, for using file contents as a cache input."""
+    digest = hashlib.sha256()
+    with open(path, "rb") as f:
+        for block in iter(lambda: f.read(65536), b""):
+            digest.update(block)
+    return digest.hexdigest()
+
+
+class StepCache:
+    """
+    Cache of step results stored under ``<root>/<step>/<key[:2]>/<key>/``.
+
+    Each entry holds an optional ``value.json`` and any number of files.
+    Disabled steps (``--no-cache``, or listed in ``--refresh``) are always
+    recomputed; refreshed steps still store their new result.
+    """
+
+    def __init__(self, root: Path = CACHE_DIR, enabled: bool = True, refresh: Iterable[str] = ()):
+        self.root = Path(root)
+        self.enabled = enabled
+        self.refresh = set(refresh)
+
+    def configure(self, enabled: bool = True, refresh: Iterable[str] = ()) -> None:
+        """Apply command line cache options."""
+        self.enabled = enabled
+        self.refresh = set(refresh)
+
+    def _entry(self, step: str, key: str) -> Path:
+        return self.root / step / key[:2] / key
+
+    def _readable(self, step: str) -> bool:
+        return self.enabled and step not in self.refresh and "all" not in self.refresh
+
+    def get(self, step: str, key: str) -> Optional[Any]:
+        """
+        Look up a cached value.
+
+        Args:
+            step: Step name
+            key: Cache key from ``cache_key``
+
+        Returns:
+            Cached value, or None on a miss (or if the step is not read from cache)
+        """
+        if not self._readable(step):
+            return None
+        value_file = self._entry(step, key) / VALUE_FILE
+        try:
+            with open(value_file, "r", encoding="utf-8") as f:
+                value = json.load(f)
+        except (FileNotFoundError, json.JSONDecodeError):
+            return None
+        logger.info(f"♻️ Cache hit: {step}")
+        return value
+
+    def put(self, step: str, key: str, value: Any) -> None:
+        """Store a JSON-serializable value (no-op when caching is disabled)."""
+        if not self.enabled or value is None:
+            return
+        entry = self._entry(step, key)
+        entry.mkdir(parents=True, exist_ok=True)
+        temp_file = entry / f"{VALUE_FILE}.tmp"
+        with open(temp_file, "w", encoding="utf-8") as f:
+            json.dump(value, f, ensure_ascii=False)
+        os.replace(temp_file, entry / VALUE_FILE)
+
+    def get_files(self, step: str, key: str, files: Dict[str, Path]) -> bool:
+        """
+        Restore cached files.
+
+        Args:
+            step: Step name
+            key: Cache key
+            files: Mapping of cached file name to destination path; entries
+                whose destination is None are optional and skipped
+
+        Returns:
+            True if every required file was restored
+        """
+        if not self._readable(step):
+            return False
+        entry = self._entry(step, key)
+        if not all((entry / name).exists() for name, dest in files.items() if dest is not None):
+            return False
+        for name, dest in files.items():
+            if dest is not None:
+                dest.parent.mkdir(parents=True, exist_ok=True)
+                shutil.copyfile(entry / name, dest)
+        logger.debug(f"Restored {', '.join(files)} from {step} cache")
+        return True
+
+    def put_files(self, step: str, key: str, files: Dict[str, Path]) -> None:
+        """Store copies of existing, non-empty files under the given names."""
+        if not self.enabled:
+            return
+        entry = self._entry(step, key)
+        entry.mkdir(parents=True, exist_ok=True)
+        for name, src in files.items():
+            if src is not None and src.exists() and src.stat().st_size > 0:
+                shutil.copyfile(src, entry / name)
+
+    def memoize(self, step: str, key: str, compute: Callable[[], Any]) -> Any:
+        """
+        Return the cached value for ``key`` or compute and store it.
+
+        None results are not cached, so failed calls are retried next run.
+        """
+        value = self.get(step, key)
+        if value is not None:
+            return value
+        value = compute()
+        self.put(step, key, value)
+        return value
+
+
+# Shared cache used by the processors, configured by the CLI entry points
+step_cache = StepCache()
+
+
+def add_cache_arguments(parser: argparse.ArgumentParser) -> None:
+    """Add ``--no-cache`` and ``--refresh STEP`` options to a command line parser."""
+    parser.add_argument("--no-cache", action="store_true",
+                        help=f"Ignore and do not update the step cache ({CACHE_DIR})")
+    parser.add_argument("--refresh", action="append", default=[], metavar="STEP",
+                        choices=CACHE_STEPS + ["all"],
+                        help=f"Recompute a cached step (repeatable): {', '.join(CACHE_STEPS)} or all")
+
+
+def configure_cache(args: argparse.Namespace) -> None:
+    """Configure the shared step cache from parsed ``add_cache_arguments`` options."""
+    step_cache.configure(enabled=not args.no_cache, refresh=args.refresh)
@@ -0,0 +1,178 @@
+"""
+Captions Module
+Builds WebVTT captions with word-level timings from TTS boundary events.
+"""
+
+import logging
+import re
+from pathlib import Path
+from typing import Dict, List
+
+logger = logging.getLogger(__name__)
+
+# Edge TTS reports offsets and durations in 100-nanosecond ticks
+TICKS_PER_SECOND = 10_000_000
+
+SENTENCE_END_WORD = re.compile(r'[.!?…。！？]["\'”’)\]」』]*$')
+CJK_WORD = re.compile(r'^[\u3000-\u30ff\u3400-\u4dbf\u4e00-\u9fff\uac00-\ud7af\uff00-\uffef]')
+
+# A cue is also closed on a long pause or when it gets too long to read
+MAX_CUE_WORDS = 24
+MAX_CUE_GAP_SECONDS = 1.0
+
+
+def boundary_from_event(event: Dict) -> Dict:
+    """
+    Convert an Edge TTS WordBoundary/SentenceBoundary event into seconds.
+
+    Args:
+        event: Event dictionary from ``Communicate.stream``
+
+    Returns:
+        Dictionary with ``type``, ``start``, ``end`` (seconds) and ``text``
+    """
+    start = event["offset"] / TICKS_PER_SECOND
+    return {
+        "type": event["type"],
+        "start": start,
+        "end": start + event["duration"] / TICKS_PER_SECOND,
+        "text": event["text"],
+    }
+
+
+def attach_punctuation(boundaries: List[Dict], text: str) -> List[Dict]:
+    """
+    Restore the punctuation that Edge TTS drops from word boundaries.
+
+    Each word is located in the source text (in order) and extended with the
+    punctuation that directly follows it, so sentence ends can be detected
+    and captions read like the original text.
+
+    Args:
+        boundaries: Boundaries for ``text``
+        text: Text that was synthesized
+
+    Returns:
+        Boundaries with punctuated word text
+    """
+    result = []
+    cursor = 0
+    for boundary in boundaries:
+        word = boundary["text"]
+        position = text.find(word, cursor) if boundary["type"] == "WordBoundary" and word else -1
+        if position == -1:
+            result.append(boundary)
+            continue
+        end = position + len(word)
+        while end < len(text) and not text[end].isspace() and not text[end].isalnum():
+            end += 1
+        result.append(dict(boundary, text=text[position:end]))
+        cursor = end
+    return result
+
+
+def offset_boundaries(boundaries: List[Dict], offset: float) -> List[Dict]:
+    """Shift boundary timings by ``offset`` seconds."""
+    return [dict(b, start=b["start"] + offset, end=b["end"] + offset) for b in boundaries]
+
+
+def format_timestamp(seconds: float) -> str:
+    """Format seconds as a WebVTT timestamp (HH:MM:SS.mmm)."""
+    millis = int(round(max(0.0, seconds) * 1000))
+    hours, millis = divmod(millis, 3_600_000)
+    minutes, millis = divmod(millis, 60_000)
+    secs, millis = divmod(millis, 1000)
+    return f"{hours:02d}:{minutes:02d}:{secs:02d}.{millis:03d}"
+
+
+def _escape(text: str) -> str:
+    return text.replace("&", "&amp;").replace("<", "&lt;").replace(">", "&gt;")
+
+
+def _join_words(words: List[str]) -> List[str]:
+    """Return the separator to put before each word (no spaces between CJK words)."""
+    separators = []
+    for index, word in enumerate(words):
+        if index == 0 or CJK_WORD.match(word) and CJK_WORD.match(words[index - 1]):
+            separators.append("")
+        else:
+            separators.append(" ")
+    return separators
+
+
+def group_words_into_cues(words: List[Dict]) -> List[List[Dict]]:
+    """
+    Group word boundaries into sentence-sized cues.
+
+    Args:
+        words: Word boundaries in playback order
+
+    Returns:
+        List of cues, each a list of word boundaries
+    """
+    cues = []
+    current = []
+    for word in words:
+        if current and (word["start"] - current[-1]["end"] > MAX_CUE_GAP_SECONDS
+                        or len(current) >= MAX_CUE_WORDS):
+            cues.append(current)
+            current = []
+        current.append(word)
+        if SENTENCE_END_WORD.search(word["text"]):
+            cues.append(current)
+            current = []
+    if current:
+        cues.append(current)
+    return cues
+
+
+def build_webvtt(boundaries: List[Dict]) -> str:
+    """
+    Build a WebVTT document with one cue per sentence.
+
+    Each cue carries inline ``<timestamp>`` tags before every word, so players
+    (and the read-along script in ``page.html``) can follow individual words.
+
+    Args:
+        boundaries: Word boundaries (other boundary types are ignored)
+
+    Returns:
+        WebVTT file content
+    """
+    words = [b for b in boundaries if b["type"] == "WordBoundary" and b["text"].strip()]
+    lines = ["WEBVTT", ""]
+
+    for index, cue in enumerate(group_words_into_cues(words), start=1):
+        separators = _join_words([w["text"] for w in cue])
+        text = ""
+        for position, (separator, word) in enumerate(zip(separators, cue)):
+            if position > 0:
+                text += f"{separator}<{format_timestamp(word['start'])}>"
+            text += _escape(word["text"])
+        lines.append(str(index))
+        lines.append(f"{format_timestamp(cue[0]['start'])} --> {format_timestamp(cue[-1]['end'])}")
+        lines.append(text)
+        lines.append("")
+
+    return "\n".join(lines)
+
+
+def write_webvtt(boundaries: List[Dict], vtt_path: Path) -> bool:
+    """
+    Write word-timed WebVTT captions.
+
+    Args:
+        boundaries: Word boundaries in playback order
+        vtt_path: Output .vtt file
+
+    Returns:
+        True if captions were written, False if there were no words
+    """
+    content = build_webvtt(boundaries)
+    if content.strip() == "WEBVTT":
+        logger.warning(f"⚠️ No word timings available, skipping captions for {vtt_path.name}")
+        return False
+
+    vtt_path.write_text(content, encoding="utf-8")
+    logger.info(f"✅ Captions saved: {vtt_path}")
+    return True
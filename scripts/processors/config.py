@@ -0,0 +1,164 @@
+"""
+Config Module
+Loads shared settings (content root, TTS provider, voice...) from zolamac.toml,
+environment variables and command line overrides.
+"""
+
+import logging
+import os
+from dataclasses import dataclass, field, fields
+from pathlib import Path
+from typing import Any, Callable, Dict, List, Optional, Union
+
+import toml
+from dotenv import load_dotenv
+
+logger = logging.getLogger(__name__)
+
+CONFIG_FILE = Path(os.getenv("ZOLAMAC_CONFIG", "zolamac.toml"))
+
+
+@dataclass
+class Settings:
+    """Settings shared by the pipelines and the ``zolamac`` CLI."""
+
+    content_root: Path = Path("content/blog")
+    output_folder: Path = Path("audio_output")
+    tts_backend: str = "edge"
+    narrate_backend: str = "local"
+    # Fall back to offline espeak-ng/Piper narration when the online backends fail
+    tts_local_fallback: bool = False
+    voice: Optional[str] = None
+    dry_run: bool = False
+    # Domain -> CSS selector(s) of the article body, overriding automatic extraction
+    extract_selectors: Dict[str, Union[str, List[str]]] = field(default_factory=dict)
+    # Polite fetching of web pages and images
+    fetch_user_agent: str = "zolamac/1.0 (+https://github.com/yoloinfinity55/zola-mac)"
+    fetch_delay: float = 1.0
+    fetch_max_per_host: int = 1
+    fetch_respect_robots: bool = True
+    fetch_timeout: float = 30.0
+    fetch_max_retries: int = 3
+    # Headless Chromium rendering of JavaScript-only pages: "auto", "always" or "never"
+    render_mode: str = "auto"
+    render_min_chars: int = 500
+    render_wait_until: str = "networkidle"
+    render_timeout: float = 30.0
+    # Bundle folder names: romanize (pinyin for Chinese) and cap the length
+    slug_transliterate: bool = False
+    slug_max_length: int = 80
+    # YouTube subtitles: languages to try after the video's own language, and
+    # an optional language to also store a translated transcript in
+    subtitle_languages: List[str] = field(default_factory=lambda: ["en"])
+    subtitle_translate_to: Optional[str] = None
+
+    def update(self, **overrides: Any) -> None:
+        """Apply overrides, ignoring None values (unset command line options)."""
+        names = {f.name for f in fields(self)}
+        for name, value in overrides.items():
+            if name not in names:
+                raise KeyError(f"Unknown setting: {name}")
+            if value is None:
+                continue
+            if name in ("content_root", "output_folder"):
+                value = Path(value)
+            setattr(self, name, value)
+
+
+def load_settings(config_file: Path = CONFIG_FILE) -> Settings:
+    """
+    Build settings from defaults, the config file and the environment.
+
+    Precedence (lowest to highest): defaults, ``zolamac.toml``, environment
+    variables (``TTS_BACKEND``, ``TTS_VOICE``, ``ZOLAMAC_CONTENT_ROOT``,
+    ``ZOLAMAC_USER_AGENT``).
+    Command line options are applied later with ``Settings.update``.
+
+    Args:
+        config_file: Path to the TOML config file (optional)
+
+    Returns:
+        Settings instance
+    """
+    load_dotenv()  # Entry points import this module before loading .env themselves
+    result = Settings()
+
+    if config_file.exists():
+        try:
+            data = toml.load(config_file)
+        except toml.TomlDecodeError as e:
+            logger.warning(f"Ignoring invalid config file {config_file}: {e}")
+            data = {}
+        paths = data.get("paths", {})
+        tts = data.get("tts", {})
+        extract = data.get("extract", {})
+        fetch = data.get("fetch", {})
+        render = data.get("render", {})
+        slugs = data.get("slugs", {})
+        subtitles = data.get("subtitles", {})
+        result.update(
+            content_root=paths.get("content_root"),
+            output_folder=paths.get("output_folder"),
+            tts_backend=tts.get("backend"),
+            narrate_backend=tts.get("narrate_backend"),
+            tts_local_fallback=tts.get("local_fallback"),
+            voice=tts.get("voice") or None,
+            extract_selectors=extract.get("selectors"),
+            fetch_user_agent=fetch.get("user_agent"),
+            fetch_delay=fetch.get("delay"),
+            fetch_max_per_host=fetch.get("max_per_host"),
+            fetch_respect_robots=fetch.get("respect_robots"),
+            fetch_timeout=fetch.get("timeout"),
+            fetch_max_retries=fetch.get("max_retries"),
+            render_mode=render.get("mode"),
+            render_min_chars=render.get("min_chars"),
+            render_wait_until=render.get("wait_until"),
+            render_timeout=render.get("timeout"),
+            slug_transliterate=slugs.get("transliterate"),
+            slug_max_length=slugs.get("max_length"),
+            subtitle_languages=subtitles.get("languages"),
+            subtitle_translate_to=subtitles.get("translate_to") or None,
+        )
+
+    result.update(
+        content_root=os.getenv("ZOLAMAC_CONTENT_ROOT"),
+        tts_backend=os.getenv("TTS_BACKEND"),
+        voice=os.getenv("TTS_VOICE"),
+        fetch_user_agent=os.getenv("ZOLAMAC_USER_AGENT"),
+    )
+    return result
+
+
+# Shared settings, loaded once and adjusted by CLI entry points
+settings = load_settings()
+
+
+def bundle_dirs(slugs: Optional[List[str]] = None,
+                predicate: Optional[Callable[[Path], bool]] = None) -> List[Path]:
+    """
+    Resolve page bundle directories under the content root.
+
+    Bundles inside series sections (subfolders with an ``_index.md``, e.g. a
+    YouTube playlist) are included.
+
+    Args:
+        slugs: Bundle folder names relative to the content root (``series/post``
+            for bundles in a series); all bundles if empty
+        predicate: Filter applied when listing all bundles
+
+    Returns:
+        Sorted list of bundle directories
+    """
+    if slugs:
+        return [settings.content_root / slug for slug in slugs]
+    if not settings.content_root.exists():
+        return []
+    candidates = []
+    for d in settings.content_root.iterdir():
+        if not d.is_dir():
+            continue
+        if (d / "_index.md").exists():
+            candidates.extend(sub for sub in d.iterdir() if sub.is_dir())
+        else:
+            candidates.append(d)
+    return sorted(d for d in candidates if predicate is None or predicate(d))
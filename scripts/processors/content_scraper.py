@@ -4,125 +4,308 @@ Handles web scraping, HTML parsing, and content extraction.
 """
 
 import re
-import time
 import logging
-from typing import Tuple, List, Optional
+from datetime import datetime
+from typing import Any, Dict, Tuple, List, Optional
 from urllib.parse import urlparse
 
-import requests
 from bs4 import BeautifulSoup
 
+from .cache import cache_key, step_cache
+from .browser_renderer import render_page
+from .config import settings
+from .http_fetcher import fetcher
+from .page_metadata import extract_page_metadata
+from .slugs import make_slug, slug_from_url
+
 logger = logging.getLogger(__name__)
 
+# Bump when extraction changes so cached scrape results are recomputed
+EXTRACTOR_VERSION = 5
+
+BLOCK_TAGS = ["h1", "h2", "h3", "h4", "h5", "h6", "p", "li", "blockquote", "pre"]
+HEADING_TAGS = ["h1", "h2", "h3"]
+
+# Page chrome that is never part of the article
+BOILERPLATE_TAGS = ["script", "style", "noscript", "template", "nav", "footer", "aside", "form",
+                    "iframe", "svg", "button", "dialog"]
+# Whole words of a class, id or role ("share-bar", "socialLinks", "comments"; not "sharepoint")
+BOILERPLATE_PATTERN = re.compile(
+    r"(?<![a-z])(?i:cookie|consent|gdpr|newsletter|subscribe|related|recommend(?:ed|ation)?|share|social|"
+    r"comment|sidebar|footer|promo|advert(?:isement)?|sponsor(?:ed)?|breadcrumb|popup|modal|navbar|navigation|"
+    r"menu|paywall)s?(?![a-z])"
+)
+# Matching elements holding more than this share of the page's text are the article, not chrome
+MAX_BOILERPLATE_TEXT_SHARE = 0.5
+
+# Extractions shorter than this are treated as failures and the next method is tried
+MIN_ARTICLE_CHARS = 250
+
 
-def slugify(url_or_title: str) -> str:
-    """Create URL-friendly slug for Zola"""
+def slugify(url_or_title: str, title: Optional[str] = None) -> str:
+    """
+    Create URL-friendly slug for Zola.
+
+    Args:
+        url_or_title: Page URL or a title
+        title: Page title, used when a URL path is generic (e.g. ``/index.html``)
+
+    Returns:
+        Slug (see ``slugs.make_slug``; not checked against existing bundles)
+    """
     if "://" in url_or_title:
-        path = urlparse(url_or_title).path.strip("/").replace("/", "-")
-        return re.sub(r"[^a-zA-Z0-9_-]", "", path) or "web-content"
-    else:
-        slug = url_or_title.lower().replace(" ", "-")
-        return re.sub(r"[^a-z0-9_-]", "", slug)
+        return slug_from_url(url_or_title, title)
+    return make_slug(url_or_title) or "untitled"
+
+
+def site_selectors(url: Optional[str]) -> List[str]:
+    """
+    Look up the configured CSS selectors for a URL's domain.
+
+    Selectors come from ``[extract.selectors]`` in zolamac.toml, keyed by
+    domain; a key also matches its subdomains (``"example.com"`` matches
+    ``blog.example.com``).
+
+    Args:
+        url: Page URL
+
+    Returns:
+        List of CSS selectors (empty if the domain has no override)
+    """
+    if not url:
+        return []
+    host = urlparse(url).netloc.lower().split(":")[0]
+    host = host[4:] if host.startswith("www.") else host
+    for domain, selectors in settings.extract_selectors.items():
+        domain = domain.lower()
+        if host == domain or host.endswith("." + domain):
+            return [selectors] if isinstance(selectors, str) else list(selectors)
+    return []
+
+
+def strip_boilerplate(soup: BeautifulSoup) -> None:
+    """
+    Remove navigation, banners, footers and similar page chrome in place.
+
+    Args:
+        soup: Parsed page (modified)
+    """
+    for el in soup.find_all(BOILERPLATE_TAGS):
+        el.decompose()
+    # Page headers hold site navigation; headers inside an article hold its title
+    for el in soup.find_all("header"):
+        if not el.find_parent(["article", "main"]):
+            el.decompose()
+    page_chars = len(soup.get_text(" ", strip=True))
+    for el in soup.find_all(True):
+        if el.decomposed or el.name in ("html", "body", "article", "main"):
+            continue
+        attrs = el.attrs or {}
+        classes = attrs.get("class") or []
+        names = [classes] if isinstance(classes, str) else list(classes)
+        names += [attrs.get("id") or "", attrs.get("role") or ""]
+        if not (any(BOILERPLATE_PATTERN.search(name) for name in names) or attrs.get("aria-hidden") == "true"):
+            continue
+        # Never drop the article itself, whatever its wrapper's class names say
+        # (e.g. "post-body has-comments" or "entry-content-related")
+        if el.find(["article", "main"]):
+            continue
+        if page_chars and len(el.get_text(" ", strip=True)) > page_chars * MAX_BOILERPLATE_TEXT_SHARE:
+            continue
+        el.decompose()
+
+
+def collect_blocks(containers: list) -> Tuple[str, List[Tuple[str, str]]]:
+    """
+    Collect text blocks and headings from containers in document order.
+
+    Blocks nested in another block (a ``<p>`` inside an ``<li>``) are only
+    read once, through their outermost block.
 
+    Args:
+        containers: Elements holding the article
 
-def fetch_content(url: str, max_retries: int = 10, backoff_factor: float = 2) -> Tuple[str, List[Tuple[str, str]], str, str]:
+    Returns:
+        Tuple of (text with one block per line, headings list)
+    """
+    texts = []
+    headings = []
+    for container in containers:
+        candidates = [container] if container.name in BLOCK_TAGS else container.find_all(BLOCK_TAGS)
+        for el in candidates:
+            if el is not container and _nested_in_block(el, container):
+                continue
+            t = el.get_text(" ", strip=True)
+            if not t:
+                continue
+            texts.append(t)
+            if el.name in HEADING_TAGS:
+                headings.append((el.name, t))
+    return "\n".join(texts), headings
+
+
+def _nested_in_block(el, container) -> bool:
+    """True if an element sits inside another block element below the container."""
+    for parent in el.parents:
+        if parent is container:
+            return False
+        if parent.name in BLOCK_TAGS:
+            return True
+    return False
+
+
+def extract_with_readability(html: str, url: Optional[str] = None) -> Optional[BeautifulSoup]:
+    """
+    Find the main article with readability-lxml's content scoring.
+
+    Args:
+        html: Page HTML
+        url: Page URL (used to resolve relative links)
+
+    Returns:
+        Parsed article fragment, or None if readability is unavailable or fails
+    """
+    try:
+        from readability import Document
+    except ImportError:
+        logger.debug("readability-lxml not installed, skipping article scoring")
+        return None
+    try:
+        summary = Document(html, url=url).summary(html_partial=True)
+    except Exception as e:
+        logger.warning(f"Readability extraction failed: {e}")
+        return None
+    article = BeautifulSoup(summary, "html.parser")
+    strip_boilerplate(article)
+    return article
+
+
+def extract_article(soup: BeautifulSoup, html: str, url: Optional[str] = None) -> Tuple[str, List[Tuple[str, str]], str]:
+    """
+    Extract the main article text, trying each method in turn.
+
+    1. Per-domain CSS selectors from zolamac.toml
+    2. readability-lxml content scoring
+    3. Every heading, paragraph and list item left after removing boilerplate
+
+    Args:
+        soup: Parsed page
+        html: Page HTML
+        url: Page URL
+
+    Returns:
+        Tuple of (text_content, headings_list, article_html)
+    """
+    page = BeautifulSoup(html, "html.parser")
+    strip_boilerplate(page)
+
+    selectors = site_selectors(url)
+    if selectors:
+        containers = [el for selector in selectors for el in page.select(selector)]
+        text, headings = collect_blocks(containers)
+        if len(text) >= MIN_ARTICLE_CHARS:
+            logger.info(f"📰 Extracted article with site selectors: {', '.join(selectors)}")
+            return text, headings, "\n".join(str(el) for el in containers)
+        logger.warning(f"⚠️ Site selectors matched too little text ({len(text)} chars), trying readability")
+
+    article = extract_with_readability(html, url)
+    if article is not None:
+        text, headings = collect_blocks([article])
+        if len(text) >= MIN_ARTICLE_CHARS:
+            # Readability can drop the leading headline; keep the page's h1 if so
+            h1 = soup.find("h1")
+            h1_text = h1.get_text(" ", strip=True) if h1 else ""
+            if h1_text and not any(tag == "h1" for tag, _ in headings) and not text.startswith(h1_text):
+                text = f"{h1_text}\n{text}"
+                headings.insert(0, ("h1", h1_text))
+            logger.info("📰 Extracted article with readability")
+            return text, headings, str(article)
+
+    logger.info("📰 Extracted article with the tag heuristic")
+    body = page.body or page
+    text, headings = collect_blocks([body])
+    return text, headings, str(body)
+
+
+def parse_html_content(html: str,
+                       url: Optional[str] = None) -> Tuple[str, List[Tuple[str, str]], str, str, str, Dict[str, Any]]:
+    """
+    Extract text, headings, title, publication date, article HTML and metadata from a page.
+
+    Args:
+        html: Page HTML
+        url: Page URL (selects per-domain extraction overrides)
+
+    Returns:
+        Tuple of (text_content, headings_list, title, publication_date, article_html,
+        page_metadata); see ``page_metadata.extract_page_metadata`` for the last item
+    """
+    soup = BeautifulSoup(html, "html.parser")
+    metadata = extract_page_metadata(soup, url)
+
+    title = metadata["title"] or "Web Audio Content"
+    pub_date = metadata["date"]
+    if not pub_date:
+        # Fallback to current date
+        pub_date = datetime.now().strftime('%Y-%m-%d')
+        metadata["sources"]["date"] = "fetched"
+        logger.warning("No publication date found in page metadata, using today's date")
+
+    # Extract the main article text
+    text, headings, article_html = extract_article(soup, html, url)
+    return text, headings, title, pub_date, article_html, metadata
+
+
+def _parse_cached(html: str, url: str) -> list:
+    """Run ``parse_html_content`` through the step cache (keyed by URL, HTML and extractor settings)."""
+    key = cache_key(url, html, EXTRACTOR_VERSION, site_selectors(url))
+    return step_cache.memoize("scrape", key, lambda: list(parse_html_content(html, url)))
+
+
+def fetch_content(url: str) -> Tuple[str, List[Tuple[str, str]], str, str, str, Dict[str, Any]]:
     """
     Fetch and extract content from a web URL.
 
+    The page is fetched through the shared polite fetcher (robots.txt,
+    per-host limits, retries, conditional re-fetch; see ``[fetch]`` in
+    ``zolamac.toml``). When the static HTML yields less than
+    ``render.min_chars`` of text (or ``render.mode`` is "always"), the page
+    is rendered in headless Chromium and extracted again. Rendered pages are
+    not cached between runs, so updates always see the live page.
+
     Args:
         url: The URL to scrape
-        max_retries: Maximum number of retry attempts
-        backoff_factor: Exponential backoff multiplier
 
     Returns:
-        Tuple of (text_content, headings_list, title, publication_date)
+        Tuple of (text_content, headings_list, title, publication_date, article_html, page_metadata);
+        ``page_metadata["fetched_with"]`` is "requests" or "playwright" (the rendered page was used)
+
+    Raises:
+        RobotsDisallowed: If the site's robots.txt disallows the URL
+        requests.RequestException: If the page cannot be fetched
     """
     logger.info(f"Fetching content from: {url}")
-
-    # Use a session with browser-like headers to avoid rate limiting
-    session = requests.Session()
-    session.headers.update({
-        'User-Agent': 'Mozilla/5.0 (Macintosh; Intel Mac OS X 10_15_7) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/91.0.4472.124 Safari/537.36',
-        'Accept': 'text/html,application/xhtml+xml,application/xml;q=0.9,image/webp,*/*;q=0.8',
-        'Accept-Language': 'en-US,en;q=0.5',
-        'Accept-Encoding': 'gzip, deflate',
-        'Connection': 'keep-alive',
-        'Upgrade-Insecure-Requests': '1',
-    })
-
-    for attempt in range(max_retries):
-        try:
-            resp = session.get(url, timeout=30)
-            resp.raise_for_status()
-            soup = BeautifulSoup(resp.text, "html.parser")
-
-            # Extract title
-            title = None
-            title_tag = soup.find('title')
-            if title_tag:
-                title = title_tag.get_text(strip=True)
-            h1_tag = soup.find('h1')
-            if h1_tag and not title:
-                title = h1_tag.get_text(strip=True)
-            if not title:
-                title = "Web Audio Content"
-
-            # Extract publication date
-            pub_date = None
-            meta_pub = soup.find('meta', property='article:published_time')
-            if meta_pub:
-                content = meta_pub.get('content')
-                if content:
-                    try:
-                        # Ensure it's a string
-                        if isinstance(content, list):
-                            content = content[0] if content else ''
-                        # Parse ISO date
-                        from datetime import datetime
-                        dt = datetime.fromisoformat(str(content).replace('Z', '+00:00'))
-                        pub_date = dt.strftime('%Y-%m-%d')
-                    except Exception as e:
-                        logger.warning(f"Could not parse publication date: {e}. Falling back to current date.")
-                        pass
-            if not pub_date:
-                # Fallback to current date
-                from datetime import datetime
-                pub_date = datetime.now().strftime('%Y-%m-%d')
-
-            # Extract visible text
-            texts = []
-            headings = []
-            for el in soup.find_all(["h1", "h2", "h3", "p", "li"]):
-                t = el.get_text(strip=True)
-                if t:
-                    texts.append(t)
-                if el.name in ["h1", "h2", "h3"]:
-                    headings.append((el.name, t))
-            text = "\n".join(texts)
-            return text, headings, title, pub_date
-
-        except requests.exceptions.HTTPError as e:
-            if e.response.status_code == 429:
-                # Add jitter to avoid thundering herd
-                import random
-                jitter = random.uniform(0.5, 1.5)
-                wait_time = (backoff_factor ** attempt) * jitter
-                logger.warning(".1f")
-                time.sleep(wait_time)
+    result = _parse_cached(fetcher.fetch_text(url), url)
+    fetched_with = "requests"
+
+    mode = settings.render_mode
+    too_short = len(result[0]) < settings.render_min_chars
+    if mode == "always" or (mode == "auto" and too_short):
+        if mode == "auto":
+            logger.info(f"🪶 Static page gave only {len(result[0])} chars of text, trying headless rendering")
+        rendered = render_page(url)
+        if rendered:
+            rendered_result = _parse_cached(rendered, url)
+            if mode == "always" or len(rendered_result[0]) > len(result[0]):
+                logger.info(f"🌐 Using rendered page ({len(rendered_result[0])} chars of text)")
+                result = rendered_result
+                fetched_with = "playwright"
             else:
-                logger.error(f"HTTP Error fetching content (status {e.response.status_code}): {e}")
-                raise
-        except Exception as e:
-            logger.error(f"Error fetching content: {e}")
-            if attempt < max_retries - 1:
-                import random
-                jitter = random.uniform(0.5, 1.5)
-                wait_time = (backoff_factor ** attempt) * jitter
-                logger.warning(".1f")
-                time.sleep(wait_time)
-            else:
-                raise
-    raise Exception(f"Failed to fetch content after {max_retries} attempts")
+                logger.info("🪶 Rendered page has no more text than the static one, keeping static extraction")
+
+    text, headings, title, pub_date, article_html, metadata = result
+    metadata = dict(metadata, fetched_with=fetched_with)
+    return text, [tuple(h) for h in headings], title, pub_date, article_html, metadata
 
 
 def extract_text_from_html(html_content: str) -> str:
@@ -164,9 +347,7 @@ def get_content_paths(slug: str) -> dict:
     Returns:
         Dictionary with paths for different file types
     """
-    from pathlib import Path
-    BASE_CONTENT = Path("content/blog")
-    folder = BASE_CONTENT / slug
+    folder = settings.content_root / slug
     folder.mkdir(parents=True, exist_ok=True)
     return {
         "md": folder / "index.md",
@@ -174,65 +355,3 @@ def get_content_paths(slug: str) -> dict:
         "txt": folder / "asset.txt",
         "json": folder / "asset.json"
     }
-
-
-def get_page_metadata(url: str) -> dict:
-    """
-    Extract metadata from a web page.
-
-    Args:
-        url: The URL to analyze
-
-    Returns:
-        Dictionary containing page metadata
-    """
-    try:
-        session = requests.Session()
-        session.headers.update({
-            'User-Agent': 'Mozilla/5.0 (Macintosh; Intel Mac OS X 10_15_7) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/91.0.4472.124 Safari/537.36'
-        })
-
-        resp = session.get(url, timeout=15)
-        resp.raise_for_status()
-        soup = BeautifulSoup(resp.text, "html.parser")
-
-        metadata = {
-            'url': url,
-            'title': None,
-            'description': None,
-            'author': None,
-            'published_date': None,
-            'modified_date': None,
-            'tags': []
-        }
-
-        # Title
-        title_tag = soup.find('title')
-        if title_tag:
-            metadata['title'] = title_tag.get_text(strip=True)
-
-        # Meta description
-        desc_meta = soup.find('meta', attrs={'name': 'description'}) or soup.find('meta', attrs={'property': 'og:description'})
-        if desc_meta:
-            metadata['description'] = desc_meta.get('content')
-
-        # Author
-        author_meta = soup.find('meta', attrs={'name': 'author'}) or soup.find('meta', attrs={'property': 'article:author'})
-        if author_meta:
-            metadata['author'] = author_meta.get('content')
-
-        # Published date
-        pub_meta = soup.find('meta', attrs={'property': 'article:published_time'})
-        if pub_meta:
-            metadata['published_date'] = pub_meta.get('content')
-
-        # Modified date
-        mod_meta = soup.find('meta', attrs={'property': 'article:modified_time'})
-        if mod_meta:
-            metadata['modified_date'] = mod_meta.get('content')
-
-        return metadata
-
-    except Exception as e:
-        logger.error(f"Error extracting metadata from {url}: {e}")
-        return {'url': url, 'error': str(e)}
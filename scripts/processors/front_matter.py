@@ -0,0 +1,210 @@
+"""
+Front Matter Module
+Reads and updates Zola TOML front matter in page bundle index.md files, and
+the generated sections of their body.
+"""
+
+import logging
+from pathlib import Path
+from typing import Any, Dict, Optional, Tuple
+
+import toml
+
+logger = logging.getLogger(__name__)
+
+FRONT_MATTER_DELIMITER = "+++"
+
+# Markers around pipeline-generated body content; re-imports replace only what is between them
+GENERATED_BEGIN = "<!-- zolamac:generated -->"
+GENERATED_END = "<!-- /zolamac:generated -->"
+
+
+def split_front_matter(content: str) -> Tuple[str, str]:
+    """
+    Split a Zola markdown file into raw front matter and body.
+
+    Args:
+        content: Full file content
+
+    Returns:
+        Tuple of (front matter TOML without delimiters, body)
+    """
+    stripped = content.lstrip("\ufeff")
+    if not stripped.startswith(FRONT_MATTER_DELIMITER):
+        return "", content
+
+    end = stripped.find(f"\n{FRONT_MATTER_DELIMITER}", len(FRONT_MATTER_DELIMITER))
+    if end == -1:
+        return "", content
+
+    front_matter = stripped[len(FRONT_MATTER_DELIMITER):end].strip("\n")
+    body = stripped[end + len(FRONT_MATTER_DELIMITER) + 1:].lstrip("\n")
+    return front_matter, body
+
+
+def read_front_matter(md_path: Path) -> Dict[str, Any]:
+    """
+    Parse the TOML front matter of a Zola markdown file.
+
+    Args:
+        md_path: Path to index.md
+
+    Returns:
+        Front matter dictionary (empty if missing or invalid)
+    """
+    try:
+        content = md_path.read_text(encoding="utf-8")
+    except FileNotFoundError:
+        return {}
+
+    front_matter, _ = split_front_matter(content)
+    if not front_matter:
+        return {}
+
+    try:
+        return toml.loads(front_matter)
+    except toml.TomlDecodeError as e:
+        logger.warning(f"Invalid front matter in {md_path}: {e}")
+        return {}
+
+
+def get_authors(front_matter: Dict[str, Any]) -> list:
+    """
+    Collect author names from the different shapes used across bundles.
+
+    Handles ``authors = [...]``, ``author = "..."`` and ``[extra] author``.
+    """
+    authors = front_matter.get("authors")
+    if isinstance(authors, list) and authors:
+        return [str(a) for a in authors]
+    for value in (front_matter.get("author"), front_matter.get("extra", {}).get("author")):
+        if value:
+            return [str(value)]
+    return []
+
+
+def _format_toml_value(value: Any) -> str:
+    """Format a single value as TOML (e.g. ``"narration.vtt"`` or ``true``)."""
+    return toml.dumps({"v": value}).split("=", 1)[1].strip()
+
+
+def build_front_matter(fields: Dict[str, Any], extra: Optional[Dict[str, Any]] = None) -> str:
+    """
+    Render a ``+++`` delimited front matter block.
+
+    Keys keep their given order; None values and empty lists are left out.
+
+    Args:
+        fields: Top-level keys (``title``, ``date``, ``authors``...)
+        extra: Keys for the ``[extra]`` table
+
+    Returns:
+        Front matter text followed by a blank line
+    """
+    lines = [f"{key} = {_format_toml_value(value)}" for key, value in fields.items() if value not in (None, [])]
+    extra_lines = [f"{key} = {_format_toml_value(value)}" for key, value in (extra or {}).items()
+                   if value not in (None, [])]
+    if extra_lines:
+        lines += ["", "[extra]"] + extra_lines
+    return f"{FRONT_MATTER_DELIMITER}\n" + "\n".join(lines) + f"\n{FRONT_MATTER_DELIMITER}\n\n"
+
+
+def set_extra_values(md_path: Path, values: Dict[str, Any]) -> bool:
+    """
+    Set keys in the ``[extra]`` table of a post's front matter.
+
+    The front matter is edited line by line so the existing formatting and
+    key order are kept; missing keys are appended to ``[extra]`` (which is
+    created if needed). A value of None removes the key.
+
+    Args:
+        md_path: Path to index.md
+        values: Keys and values to set (None to remove)
+
+    Returns:
+        True if the file was updated, False if it has no front matter
+    """
+    content = md_path.read_text(encoding="utf-8")
+    front_matter, body = split_front_matter(content)
+    if not front_matter and not content.lstrip("\ufeff").startswith(FRONT_MATTER_DELIMITER):
+        logger.warning(f"No front matter in {md_path}, cannot set {', '.join(values)}")
+        return False
+
+    lines = front_matter.split("\n") if front_matter else []
+    extra_start = next((i for i, line in enumerate(lines) if line.strip() == "[extra]"), None)
+    if extra_start is None:
+        if all(value is None for value in values.values()):
+            return True
+        lines += ["", "[extra]"]
+        extra_start = len(lines) - 1
+
+    extra_end = next(
+        (i for i in range(extra_start + 1, len(lines)) if lines[i].strip().startswith("[")),
+        len(lines),
+    )
+
+    remaining = dict(values)
+    removed = set()
+    for i in range(extra_start + 1, extra_end):
+        key = lines[i].split("=", 1)[0].strip()
+        if "=" in lines[i] and key in remaining:
+            value = remaining.pop(key)
+            if value is None:
+                removed.add(i)
+            else:
+                lines[i] = f"{key} = {_format_toml_value(value)}"
+
+    insert_at = extra_end
+    while insert_at > extra_start + 1 and not lines[insert_at - 1].strip():
+        insert_at -= 1
+    lines[insert_at:insert_at] = [
+        f"{key} = {_format_toml_value(value)}" for key, value in remaining.items() if value is not None
+    ]
+    lines = [line for i, line in enumerate(lines) if i not in removed]
+
+    new_front_matter = "\n".join(lines).strip("\n")
+    md_path.write_text(
+        f"{FRONT_MATTER_DELIMITER}\n{new_front_matter}\n{FRONT_MATTER_DELIMITER}\n\n{body}",
+        encoding="utf-8",
+    )
+    return True
+
+
+def wrap_generated(body: str) -> str:
+    """Put generated-section markers around body content."""
+    return f"{GENERATED_BEGIN}\n{body.strip()}\n{GENERATED_END}\n"
+
+
+def has_generated_section(md_path: Path) -> bool:
+    """Check whether an index.md has generated-section markers."""
+    try:
+        content = md_path.read_text(encoding="utf-8")
+    except FileNotFoundError:
+        return False
+    return GENERATED_BEGIN in content and GENERATED_END in content
+
+
+def replace_generated_section(md_path: Path, body: str) -> bool:
+    """
+    Replace the generated section of an index.md, keeping everything else.
+
+    Front matter and any text outside the markers (hand-written intros,
+    notes, edits) are left exactly as they are.
+
+    Args:
+        md_path: Path to index.md
+        body: New generated content (without markers)
+
+    Returns:
+        True if the file was updated, False if it has no generated section
+    """
+    content = md_path.read_text(encoding="utf-8")
+    start = content.find(GENERATED_BEGIN)
+    end = content.find(GENERATED_END, start + 1)
+    if start == -1 or end == -1:
+        logger.warning(f"No generated section in {md_path}")
+        return False
+
+    updated = content[:start] + wrap_generated(body).rstrip("\n") + content[end + len(GENERATED_END):]
+    md_path.write_text(updated, encoding="utf-8")
+    return True
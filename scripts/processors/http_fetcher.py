@@ -0,0 +1,240 @@
+"""
+HTTP Fetcher Module
+Polite page fetching for imports: robots.txt, per-host rate and concurrency
+limits, conditional re-fetches (ETag / If-Modified-Since) and an honest
+User-Agent.
+"""
+
+import logging
+import random
+import threading
+import time
+from contextlib import contextmanager
+from typing import Dict, Iterator, Optional
+from urllib.parse import urlparse
+from urllib.robotparser import RobotFileParser
+
+import requests
+
+from .cache import cache_key, step_cache
+from .config import settings
+from .key_pool import parse_retry_after
+
+logger = logging.getLogger(__name__)
+
+# Statuses worth retrying; everything else fails immediately
+RETRY_STATUSES = {429, 500, 502, 503, 504}
+MAX_RETRY_WAIT_SECONDS = 120
+
+
+class RobotsDisallowed(Exception):
+    """Raised when robots.txt does not allow fetching a URL."""
+
+
+class PoliteFetcher:
+    """
+    Shared fetcher that keeps per-host state across every request in a run.
+
+    Each host gets a semaphore (``fetch.max_per_host`` concurrent requests)
+    and a minimum interval between requests (``fetch.delay`` seconds, or the
+    site's robots.txt ``Crawl-delay`` if longer).
+    """
+
+    def __init__(self):
+        self._session: Optional[requests.Session] = None
+        self._lock = threading.Lock()
+        self._robots: Dict[str, Optional[RobotFileParser]] = {}
+        self._semaphores: Dict[str, threading.Semaphore] = {}
+        self._next_request: Dict[str, float] = {}
+
+    @property
+    def session(self) -> requests.Session:
+        if self._session is None:
+            self._session = requests.Session()
+            self._session.headers.update({
+                "User-Agent": settings.fetch_user_agent,
+                "Accept": "text/html,application/xhtml+xml,application/xml;q=0.9,*/*;q=0.8",
+                "Accept-Language": "en-US,en;q=0.5",
+            })
+        return self._session
+
+    def robots_for(self, url: str) -> Optional[RobotFileParser]:
+        """
+        Load (once per host) the robots.txt rules for a URL's site.
+
+        Returns:
+            Parsed rules, or None if the site has no usable robots.txt
+        """
+        parsed = urlparse(url)
+        origin = f"{parsed.scheme}://{parsed.netloc}"
+        with self._lock:
+            if origin in self._robots:
+                return self._robots[origin]
+
+        rules = None
+        try:
+            resp = self.session.get(f"{origin}/robots.txt", timeout=settings.fetch_timeout)
+            if resp.status_code == 200:
+                rules = RobotFileParser()
+                rules.parse(resp.text.splitlines())
+            elif resp.status_code in (401, 403):
+                # Sites that hide robots.txt behind auth are treated as disallowing crawlers
+                rules = RobotFileParser()
+                rules.disallow_all = True
+        except requests.RequestException as e:
+            logger.warning(f"⚠️ Could not read {origin}/robots.txt ({e}); assuming crawling is allowed")
+
+        with self._lock:
+            self._robots[origin] = rules
+        return rules
+
+    def check_robots(self, url: str) -> float:
+        """
+        Raise RobotsDisallowed if robots.txt forbids the URL.
+
+        Returns:
+            The site's Crawl-delay in seconds (0 if none)
+        """
+        if not settings.fetch_respect_robots:
+            return 0.0
+        rules = self.robots_for(url)
+        if rules is None:
+            return 0.0
+        agent = settings.fetch_user_agent
+        if not rules.can_fetch(agent, url):
+            raise RobotsDisallowed(f"robots.txt disallows fetching {url}")
+        return float(rules.crawl_delay(agent) or 0)
+
+    def _wait_turn(self, host: str, interval: float) -> None:
+        """Sleep until the host's minimum request interval has passed, then book the next slot."""
+        with self._lock:
+            now = time.monotonic()
+            start = max(now, self._next_request.get(host, 0.0))
+            self._next_request[host] = start + interval
+        if start > now:
+            logger.info(f"⏳ Waiting {start - now:.1f}s before the next request to {host}")
+            time.sleep(start - now)
+
+    def _semaphore(self, host: str) -> threading.Semaphore:
+        with self._lock:
+            if host not in self._semaphores:
+                self._semaphores[host] = threading.Semaphore(max(1, settings.fetch_max_per_host))
+            return self._semaphores[host]
+
+    @contextmanager
+    def slot(self, url: str) -> Iterator[None]:
+        """
+        Hold a request slot for a URL's host outside of ``get`` (e.g. a browser).
+
+        Applies the same robots.txt check, concurrency limit and request
+        interval as ``get``.
+
+        Raises:
+            RobotsDisallowed: If robots.txt forbids the URL
+        """
+        host = urlparse(url).netloc
+        interval = max(settings.fetch_delay, self.check_robots(url))
+        with self._semaphore(host):
+            self._wait_turn(host, interval)
+            yield
+
+    def get(self, url: str, **kwargs) -> requests.Response:
+        """
+        GET a URL politely, retrying rate-limited and failed requests.
+
+        Args:
+            url: URL to fetch
+            **kwargs: Passed through to ``requests.Session.get``
+
+        Returns:
+            Response (may be 304 Not Modified for conditional requests)
+
+        Raises:
+            RobotsDisallowed: If robots.txt forbids the URL
+            requests.RequestException: If the request fails after retries
+        """
+        host = urlparse(url).netloc
+        interval = max(settings.fetch_delay, self.check_robots(url))
+        kwargs.setdefault("timeout", settings.fetch_timeout)
+
+        attempts = max(1, settings.fetch_max_retries + 1)
+        for attempt in range(1, attempts + 1):
+            with self._semaphore(host):
+                self._wait_turn(host, interval)
+                try:
+                    resp = self.session.get(url, **kwargs)
+                except (requests.ConnectionError, requests.Timeout) as e:
+                    if attempt == attempts:
+                        raise
+                    wait = self._backoff(attempt)
+                    logger.warning(f"⚠️ {type(e).__name__} fetching {url}; retrying in {wait:.1f}s "
+                                   f"(attempt {attempt}/{attempts})")
+                    time.sleep(wait)
+                    continue
+
+            if resp.status_code in RETRY_STATUSES and attempt < attempts:
+                wait = parse_retry_after(resp.headers.get("Retry-After")) or self._backoff(attempt)
+                if wait > MAX_RETRY_WAIT_SECONDS:
+                    logger.error(f"❌ {host} asked us to wait {wait:.0f}s; giving up on {url}")
+                    break
+                logger.warning(f"⚠️ HTTP {resp.status_code} from {host}; retrying in {wait:.1f}s "
+                               f"(attempt {attempt}/{attempts})")
+                # Slow down every later request to this host as well
+                with self._lock:
+                    self._next_request[host] = max(self._next_request.get(host, 0.0), time.monotonic() + wait)
+                time.sleep(wait)
+                continue
+            break
+
+        resp.raise_for_status()
+        return resp
+
+    def fetch_text(self, url: str) -> str:
+        """
+        Fetch a page's text, re-validating a previously fetched copy.
+
+        The ETag and Last-Modified of each fetched page are kept in the step
+        cache (step ``fetch``); the next fetch sends If-None-Match /
+        If-Modified-Since and reuses the stored text on 304 Not Modified.
+
+        Args:
+            url: Page URL
+
+        Returns:
+            Decoded page text
+        """
+        key = cache_key("conditional-get", url)
+        stored = step_cache.get("fetch", key)
+        headers = {}
+        if stored:
+            if stored.get("etag"):
+                headers["If-None-Match"] = stored["etag"]
+            if stored.get("last_modified"):
+                headers["If-Modified-Since"] = stored["last_modified"]
+
+        resp = self.get(url, headers=headers)
+        if resp.status_code == 304 and stored:
+            logger.info(f"♻️ Not modified since last fetch: {url}")
+            return stored["text"]
+
+        # Without a charset header requests assumes ISO-8859-1, which garbles UTF-8 pages
+        if "charset" not in resp.headers.get("Content-Type", "").lower():
+            resp.encoding = resp.apparent_encoding
+        text = resp.text
+
+        if resp.headers.get("ETag") or resp.headers.get("Last-Modified"):
+            step_cache.put("fetch", key, {
+                "etag": resp.headers.get("ETag"),
+                "last_modified": resp.headers.get("Last-Modified"),
+                "text": text,
+            })
+        return text
+
+    @staticmethod
+    def _backoff(attempt: int) -> float:
+        """Exponential backoff with jitter: ~2s, 4s, 8s..."""
+        return (2 ** attempt) * random.uniform(0.75, 1.25)
+
+
+# Shared fetcher so limits apply across a whole batch run
+fetcher = PoliteFetcher()
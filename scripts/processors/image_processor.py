@@ -3,15 +3,20 @@ Image Processor Module
 Handles thumbnail generation, image processing, and visual asset creation.
 """
 
+import hashlib
 import logging
 from pathlib import Path
-from typing import Optional
+from typing import List, Optional
 from urllib.parse import quote
 
 import requests
 from PIL import Image
 import io
 
+from .ai_processor import groq_post_json, has_groq_keys
+from .cache import cache_key, step_cache
+from .http_fetcher import fetcher
+
 logger = logging.getLogger(__name__)
 
 
@@ -28,7 +33,7 @@ def generate_image_keywords_with_ai(text: str, title: str, post_slug: str, groq_
     Returns:
         Comma-separated keywords for image search
     """
-    if not groq_api_key:
+    if not has_groq_keys(groq_api_key):
         logger.warning("GROQ_API_KEY not set. Using fallback keywords.")
         return "technology blog article"
 
@@ -57,7 +62,7 @@ def generate_image_keywords_with_ai(text: str, title: str, post_slug: str, groq_
     """
 
     try:
-        headers = {"Authorization": f"Bearer {groq_api_key}", "Content-Type": "application/json"}
+        headers = {"Content-Type": "application/json"}
         payload = {
             "model": "llama-3.3-70b-versatile",
             "messages": [{"role": "user", "content": prompt}],
@@ -65,9 +70,9 @@ def generate_image_keywords_with_ai(text: str, title: str, post_slug: str, groq_
             "max_tokens": 120
         }
 
-        resp = requests.post("https://api.groq.com/openai/v1/chat/completions", headers=headers, json=payload, timeout=30)
-        resp.raise_for_status()
-        keywords = resp.json()["choices"][0]["message"]["content"].strip()
+        data = groq_post_json("https://api.groq.com/openai/v1/chat/completions", groq_api_key, "keywords",
+                              headers=headers, json=payload, timeout=30)
+        keywords = data["choices"][0]["message"]["content"].strip()
 
         # Clean and validate keywords
         keywords = keywords.strip('",.').replace('"', '').replace("'", "")
@@ -221,6 +226,11 @@ def download_youtube_thumbnail(thumbnail_url: str, filepath: Path) -> bool:
     Returns:
         True if successful, False otherwise
     """
+    key = cache_key(thumbnail_url)
+    if step_cache.get_files("thumbnail", key, {"thumbnail.jpg": filepath}):
+        logger.info("♻️ Using cached YouTube thumbnail")
+        return True
+
     logger.info(f"📥 Downloading YouTube thumbnail: {thumbnail_url}")
 
     try:
@@ -231,6 +241,7 @@ def download_youtube_thumbnail(thumbnail_url: str, filepath: Path) -> bool:
         with open(filepath, 'wb') as f:
             f.write(resp.content)
 
+        step_cache.put_files("thumbnail", key, {"thumbnail.jpg": filepath})
         logger.info(f"✅ YouTube thumbnail downloaded: {filepath}")
         return True
     except Exception as e:
@@ -260,6 +271,12 @@ def generate_blog_thumbnail(text: str, title: str, post_dir: Path, slug: str, gr
         logger.info("Thumbnail already exists, skipping generation")
         return True
 
+    # Same post content -> same thumbnail, without new AI or Unsplash calls
+    key = cache_key(title, slug, text[:1200])
+    if step_cache.get_files("thumbnail", key, {"thumbnail.jpg": thumb_path}):
+        logger.info("♻️ Using cached thumbnail")
+        return True
+
     try:
         # Generate keywords using AI
         keywords = generate_image_keywords_with_ai(text, title, slug, groq_api_key)
@@ -271,6 +288,7 @@ def generate_blog_thumbnail(text: str, title: str, post_dir: Path, slug: str, gr
         success = download_and_process_image(image_url, thumb_path)
 
         if success:
+            step_cache.put_files("thumbnail", key, {"thumbnail.jpg": thumb_path})
             logger.info(f"✅ Thumbnail generated: {thumb_path}")
         return success
 
@@ -321,20 +339,31 @@ def get_image_dimensions(image_file: Path) -> Optional[tuple]:
         return None
 
 
-def optimize_image(image_file: Path, max_width: int = 1200, quality: int = 85) -> bool:
+def optimize_image(image_file: Path, max_width: int = 1200, quality: int = 85, image_format: str = "JPEG") -> bool:
     """
     Optimize an image by resizing and compressing.
 
     Args:
-        image_file: Path to image file
+        image_file: Path to image file (rewritten in place)
         max_width: Maximum width to resize to
-        quality: JPEG quality (1-100)
+        quality: JPEG/WebP quality (1-100)
+        image_format: Output format understood by Pillow (``JPEG`` or ``WEBP``)
 
     Returns:
         True if optimization was successful
     """
     try:
         with Image.open(image_file) as img:
+            img.load()
+            # JPEG has no alpha channel; WebP keeps transparency
+            if image_format == "JPEG" and img.mode != "RGB":
+                img = img.convert("RGB")
+            elif image_format == "WEBP" and img.mode not in ("RGB", "RGBA"):
+                img = img.convert("RGBA" if "transparency" in img.info or img.mode in ("LA", "PA") else "RGB")
+
+            save_options = {"quality": quality}
+            save_options.update({"method": 6} if image_format == "WEBP" else {"optimize": True})
+
             # Only resize if image is larger than max_width
             if img.width > max_width:
                 # Calculate new height maintaining aspect ratio
@@ -345,14 +374,130 @@ def optimize_image(image_file: Path, max_width: int = 1200, quality: int = 85) -
                 resized_img = img.resize((max_width, new_height), Image.Resampling.LANCZOS)
 
                 # Save optimized image
-                resized_img.save(image_file, "JPEG", quality=quality, optimize=True)
+                resized_img.save(image_file, image_format, **save_options)
                 logger.info(f"Optimized image {image_file} to {max_width}x{new_height}")
             else:
                 # Just recompress if not resizing
-                img.save(image_file, "JPEG", quality=quality, optimize=True)
+                img.save(image_file, image_format, **save_options)
                 logger.info(f"Recompressed image {image_file}")
 
         return True
     except Exception as e:
         logger.error(f"Image optimization failed for {image_file}: {e}")
         return False
+
+
+class BundleImageLocalizer:
+    """
+    Downloads article images into a page bundle as ``img-<content hash>.webp``.
+
+    Instances are ``markdown_converter`` image resolvers: called with an image
+    URL and its alt text, they return the bundle-relative file name. Names
+    come from the image content, so identical images share one file and
+    updates keep existing names. Images that cannot be downloaded keep their
+    original URL. ``records`` lists each file with its source URLs and alt
+    text for asset.json; ``prune`` removes images the post no longer uses.
+    """
+
+    def __init__(self, post_dir: Path, max_width: int = 1200, quality: int = 80):
+        self.post_dir = post_dir
+        self.max_width = max_width
+        self.quality = quality
+        self.records = []
+        self.failed = []
+        self._by_url = {}
+        self._by_hash = {}
+
+    def __call__(self, url: str, alt: str) -> Optional[str]:
+        if url in self._by_url:
+            return self._by_url[url]
+
+        try:
+            resp = fetcher.get(url)
+            data = resp.content
+        except Exception as e:
+            logger.warning(f"⚠️ Could not download image {url}: {e}")
+            self.failed.append(url)
+            return url
+
+        digest = hashlib.sha256(data).hexdigest()
+        record = self._by_hash.get(digest)
+        if record is None:
+            name = self._save(data, digest, resp.headers.get("Content-Type", ""))
+            if name is None:
+                self.failed.append(url)
+                return url
+            if name == "":
+                self._by_url[url] = None  # Tracking pixel or spacer
+                return None
+            record = {"file": name, "source_sha256": digest, "urls": [], "alt": alt}
+            self._by_hash[digest] = record
+            self.records.append(record)
+        else:
+            logger.info(f"♻️ Duplicate image, reusing {record['file']}: {url}")
+
+        record["urls"].append(url)
+        record["alt"] = record["alt"] or alt
+        self._by_url[url] = record["file"]
+        return record["file"]
+
+    def prune(self, markdown_path: Path) -> List[str]:
+        """
+        Delete bundle images (``img-*``) that the Markdown file no longer references.
+
+        Args:
+            markdown_path: The bundle's index.md
+
+        Returns:
+            Names of the deleted files
+        """
+        if not markdown_path.exists():
+            return []
+        content = markdown_path.read_text(encoding="utf-8")
+        removed = []
+        for path in sorted(self.post_dir.glob("img-*")):
+            if path.is_file() and path.name not in content:
+                path.unlink()
+                removed.append(path.name)
+        if removed:
+            logger.info(f"🧹 Removed {len(removed)} unused image(s): {', '.join(removed)}")
+        return removed
+
+    def _save(self, data: bytes, digest: str, content_type: str) -> Optional[str]:
+        """Write image bytes into the bundle; returns its name, "" to drop it, None on failure."""
+        stem = f"img-{digest[:12]}"
+        existing = sorted(self.post_dir.glob(f"{stem}.*"))
+        if existing:
+            return existing[0].name
+
+        if "svg" in content_type or data.lstrip()[:5] in (b"<?xml", b"<svg "):
+            name = f"{stem}.svg"
+            (self.post_dir / name).write_bytes(data)
+            return name
+
+        try:
+            with Image.open(io.BytesIO(data)) as img:
+                width, height = img.size
+                animated = getattr(img, "is_animated", False)
+                source_format = (img.format or "").lower()
+        except Exception as e:
+            logger.warning(f"⚠️ Not a readable image ({content_type or 'unknown type'}): {e}")
+            return None
+
+        if width <= 2 or height <= 2:
+            return ""
+
+        # Animations would lose every frame but the first
+        if animated:
+            name = f"{stem}.{source_format or 'gif'}"
+            (self.post_dir / name).write_bytes(data)
+            return name
+
+        name = f"{stem}.webp"
+        path = self.post_dir / name
+        path.write_bytes(data)
+        if not optimize_image(path, self.max_width, self.quality, image_format="WEBP"):
+            path.unlink(missing_ok=True)
+            return None
+        logger.info(f"🖼️ Saved article image {name}")
+        return name
@@ -0,0 +1,311 @@
+"""
+API Key Pool Module
+Tracks the health of multiple API keys per provider and picks the best one.
+"""
+
+import hashlib
+import json
+import logging
+import os
+import re
+import threading
+import time
+from email.utils import parsedate_to_datetime
+from pathlib import Path
+from typing import Any, Callable, Collection, Dict, List, Optional
+
+logger = logging.getLogger(__name__)
+
+DEFAULT_STATE_FILE = Path(os.getenv("KEY_POOL_STATE", ".key_pool_state.json"))
+
+# Cooldowns in seconds
+DEFAULT_RATE_LIMIT_COOLDOWN = 60
+MAX_COOLDOWN = 3600
+ERROR_COOLDOWN = 10
+ERRORS_BEFORE_COOLDOWN = 3
+# Longest acquire() sleeps for a cooling key; longer cooldowns return None so callers can fall back
+MAX_ACQUIRE_WAIT = float(os.getenv("KEY_POOL_MAX_WAIT", "60"))
+
+# Gemini reports quota resets as e.g. "retryDelay": "37s" in the error body
+RETRY_DELAY_PATTERN = re.compile(r"retry[_ ]?delay['\"]?\s*[:=]\s*['\"]?(\d+(?:\.\d+)?)s", re.IGNORECASE)
+
+_state_lock = threading.Lock()
+
+
+def parse_retry_after(value: Optional[str]) -> Optional[float]:
+    """
+    Parse a Retry-After header value (seconds or HTTP date) into seconds.
+
+    Args:
+        value: Header value
+
+    Returns:
+        Seconds to wait, or None if missing/unparseable
+    """
+    if not value:
+        return None
+    try:
+        return max(0.0, float(value))
+    except ValueError:
+        pass
+    try:
+        return max(0.0, parsedate_to_datetime(value).timestamp() - time.time())
+    except (TypeError, ValueError):
+        return None
+
+
+def retry_after_from_error(error: Exception) -> Optional[float]:
+    """
+    Extract a retry-after hint from an API exception.
+
+    Looks at a ``Retry-After`` header on an attached HTTP response first, then
+    at a ``retryDelay`` value in the error message.
+
+    Args:
+        error: Exception raised by an API client
+
+    Returns:
+        Seconds to wait, or None if no hint was found
+    """
+    response = getattr(error, "response", None)
+    headers = getattr(response, "headers", None)
+    if headers:
+        retry_after = parse_retry_after(headers.get("Retry-After"))
+        if retry_after is not None:
+            return retry_after
+
+    match = RETRY_DELAY_PATTERN.search(str(error))
+    if match:
+        return float(match.group(1))
+    return None
+
+
+def status_code_from_error(error: Exception) -> Optional[int]:
+    """Best-effort HTTP status code from requests or google-genai exceptions."""
+    response = getattr(error, "response", None)
+    status = getattr(response, "status_code", None)
+    if status is None:
+        status = getattr(error, "code", None) or getattr(error, "status_code", None)
+    try:
+        return int(status) if status is not None else None
+    except (TypeError, ValueError):
+        return None
+
+
+def fingerprint(key: str) -> str:
+    """Stable, non-secret identifier for a key (used in logs and the state file)."""
+    return hashlib.sha256(key.encode("utf-8")).hexdigest()[:12]
+
+
+def load_keys_from_env(prefix: str) -> List[str]:
+    """
+    Collect every key named ``PREFIX`` or ``PREFIX_<n>`` from the environment.
+
+    Args:
+        prefix: Environment variable prefix, e.g. ``GEMINI_API_KEY``
+
+    Returns:
+        De-duplicated keys, ``PREFIX`` first and then by number
+    """
+    pattern = re.compile(rf"^{re.escape(prefix)}(?:_(\d+))?$")
+    found = []
+    for name, value in os.environ.items():
+        match = pattern.match(name)
+        if match and value.strip():
+            found.append((int(match.group(1) or 0), value.strip()))
+
+    keys = []
+    for _, value in sorted(found):
+        if value not in keys:
+            keys.append(value)
+    return keys
+
+
+class APIKeyPool:
+    """
+    Pool of API keys for one provider with persisted per-key health.
+
+    Each key records successes, failures, rate limits and a ``cooldown_until``
+    timestamp. ``acquire`` returns the healthiest key that is not cooling down,
+    so an exhausted key is backed off on its own instead of pausing the run.
+    """
+
+    def __init__(self, provider: str, keys: List[str], state_file: Path = DEFAULT_STATE_FILE):
+        self.provider = provider
+        self.keys = [k for k in dict.fromkeys(keys) if k]
+        self.state_file = Path(state_file)
+
+    @classmethod
+    def from_env(cls, provider: str, prefix: str, extra_keys: Optional[List[str]] = None,
+                 state_file: Path = DEFAULT_STATE_FILE) -> "APIKeyPool":
+        """
+        Build a pool from ``PREFIX`` / ``PREFIX_<n>`` environment variables.
+
+        Args:
+            provider: Provider name used as the state file section
+            prefix: Environment variable prefix
+            extra_keys: Additional keys (e.g. passed explicitly by a caller)
+            state_file: Path of the persisted state file
+
+        Returns:
+            APIKeyPool instance
+        """
+        keys = load_keys_from_env(prefix) + [k for k in (extra_keys or []) if k]
+        return cls(provider, keys, state_file)
+
+    def __len__(self) -> int:
+        return len(self.keys)
+
+    def _load_state(self) -> Dict[str, Any]:
+        try:
+            with open(self.state_file, "r", encoding="utf-8") as f:
+                return json.load(f)
+        except (FileNotFoundError, json.JSONDecodeError):
+            return {}
+
+    def _save_state(self, state: Dict[str, Any]) -> None:
+        temp_file = self.state_file.with_suffix(".tmp")
+        with open(temp_file, "w", encoding="utf-8") as f:
+            json.dump(state, f, indent=2)
+        os.replace(temp_file, self.state_file)
+
+    def _update(self, key: str, changes_for: Callable[[Dict[str, Any]], Dict[str, Any]]) -> Dict[str, Any]:
+        """
+        Update a key's record and persist the state file.
+
+        ``changes_for`` gets the current record and returns the fields to
+        change; it runs under the state lock so concurrent reports are not lost.
+        """
+        with _state_lock:
+            state = self._load_state()
+            record = state.setdefault(self.provider, {}).setdefault(fingerprint(key), {
+                "successes": 0,
+                "failures": 0,
+                "rate_limited": 0,
+                "consecutive_failures": 0,
+                "cooldown_until": 0,
+                "last_error": None,
+            })
+            record.update(changes_for(record))
+            record["last_used"] = time.time()
+            self._save_state(state)
+            return record
+
+    def _records(self) -> Dict[str, Dict[str, Any]]:
+        with _state_lock:
+            return self._load_state().get(self.provider, {})
+
+    def _health(self, record: Dict[str, Any]) -> float:
+        """Higher is healthier: success ratio, penalised by recent consecutive failures."""
+        successes = record.get("successes", 0)
+        attempts = successes + record.get("failures", 0) + record.get("rate_limited", 0)
+        ratio = (successes + 1) / (attempts + 2)
+        return ratio - 0.25 * record.get("consecutive_failures", 0)
+
+    def acquire(self, wait: bool = True, exclude: Optional[Collection[str]] = None) -> Optional[str]:
+        """
+        Pick the healthiest key that is not cooling down.
+
+        Args:
+            wait: If every key is cooling down, sleep until the first one is
+                available instead of returning None (only if that is at most
+                ``MAX_ACQUIRE_WAIT`` seconds away)
+            exclude: Keys not to pick (e.g. the ones a request already tried)
+
+        Returns:
+            API key, or None if the pool has no other keys (or all keys are
+            cooling down and ``wait`` is False or the wait would be too long)
+        """
+        exclude = exclude or ()
+        candidates = [(index, key) for index, key in enumerate(self.keys) if key not in exclude]
+        if not candidates:
+            return None
+
+        records = self._records()
+        now = time.time()
+        ranked = []
+        for index, key in candidates:
+            record = records.get(fingerprint(key), {})
+            # Least recently used breaks ties so healthy keys share the load
+            ranked.append((record.get("cooldown_until", 0), -self._health(record), record.get("last_used", 0), index, key))
+
+        available = [entry for entry in ranked if entry[0] <= now]
+        if available:
+            key = min(available, key=lambda entry: entry[1:4])[-1]
+            logger.debug(f"{self.provider}: using key {fingerprint(key)}")
+            return key
+
+        soonest = min(ranked)
+        wait_time = soonest[0] - now
+        if not wait:
+            return None
+        if wait_time > MAX_ACQUIRE_WAIT:
+            logger.warning(f"All {self.provider} keys are cooling down for at least {wait_time:.0f}s; not waiting")
+            return None
+        logger.warning(f"All {self.provider} keys are cooling down; waiting {wait_time:.1f}s for key {fingerprint(soonest[-1])}")
+        time.sleep(wait_time)
+        return soonest[-1]
+
+    def report_success(self, key: str) -> None:
+        """Record a successful call for ``key``."""
+        self._update(key, lambda record: {
+            "successes": record.get("successes", 0) + 1,
+            "consecutive_failures": 0,
+            "cooldown_until": 0,
+        })
+
+    def report_failure(self, key: str, status_code: Optional[int] = None, retry_after: Optional[float] = None,
+                       error: Optional[str] = None) -> None:
+        """
+        Record a failed call for ``key`` and back it off if needed.
+
+        A 429 puts the key on cooldown for ``retry_after`` seconds (or an
+        exponential default). Other errors only cool the key down after
+        several consecutive failures.
+
+        Args:
+            key: The key that was used
+            status_code: HTTP status code, if known
+            retry_after: Retry-after hint in seconds, if known
+            error: Error message to record
+        """
+        def changes_for(record: Dict[str, Any]) -> Dict[str, Any]:
+            consecutive = record.get("consecutive_failures", 0) + 1
+            changes = {"consecutive_failures": consecutive, "last_error": (error or "")[:200]}
+
+            if status_code == 429:
+                cooldown = retry_after if retry_after is not None else min(
+                    MAX_COOLDOWN, DEFAULT_RATE_LIMIT_COOLDOWN * 2 ** (consecutive - 1)
+                )
+                changes.update({"rate_limited": record.get("rate_limited", 0) + 1,
+                                "cooldown_until": time.time() + cooldown})
+                logger.warning(f"{self.provider}: key {fingerprint(key)} rate limited, cooling down for {cooldown:.1f}s")
+            else:
+                changes["failures"] = record.get("failures", 0) + 1
+                if consecutive >= ERRORS_BEFORE_COOLDOWN:
+                    cooldown = min(MAX_COOLDOWN, ERROR_COOLDOWN * 2 ** (consecutive - ERRORS_BEFORE_COOLDOWN))
+                    changes["cooldown_until"] = time.time() + cooldown
+                    logger.warning(f"{self.provider}: key {fingerprint(key)} failed {consecutive} times in a row, "
+                                   f"cooling down for {cooldown:.1f}s")
+            return changes
+
+        self._update(key, changes_for)
+
+    def report_error(self, key: str, error: Exception) -> None:
+        """Record a failure from an exception, extracting status and retry hints."""
+        self.report_failure(key, status_code_from_error(error), retry_after_from_error(error), str(error))
+
+    def summary(self) -> List[Dict[str, Any]]:
+        """Per-key health overview (fingerprints only) for logging."""
+        records = self._records()
+        now = time.time()
+        return [
+            {
+                "key": fingerprint(key),
+                "successes": records.get(fingerprint(key), {}).get("successes", 0),
+                "failures": records.get(fingerprint(key), {}).get("failures", 0),
+                "rate_limited": records.get(fingerprint(key), {}).get("rate_limited", 0),
+                "cooling_down_for": max(0.0, records.get(fingerprint(key), {}).get("cooldown_until", 0) - now),
+            }
+            for key in self.keys
+        ]
@@ -0,0 +1,352 @@
+"""
+Markdown Converter Module
+Converts extracted article HTML into Markdown for Zola, keeping headings in
+place along with links, lists, code blocks, tables, images and blockquotes.
+"""
+
+import logging
+import re
+from typing import Callable, List, Optional
+from urllib.parse import urljoin
+
+from bs4 import BeautifulSoup, Comment, NavigableString
+
+logger = logging.getLogger(__name__)
+
+# Called with (absolute image URL, alt text); returns the Markdown image path,
+# or None to drop the image
+ImageResolver = Callable[[str, str], Optional[str]]
+
+HEADING_TAGS = {"h1", "h2", "h3", "h4", "h5", "h6"}
+SKIPPED_TAGS = {"script", "style", "noscript", "template", "button", "form", "input", "select",
+                "textarea", "svg", "canvas", "head", "title", "meta", "link"}
+BLOCK_TAGS = HEADING_TAGS | {"p", "ul", "ol", "pre", "blockquote", "table", "hr", "figure", "figcaption",
+                             "div", "section", "article", "main", "header", "aside", "details", "dl",
+                             "dt", "dd", "iframe", "video", "audio", "picture", "center", "address",
+                             "html", "body"}
+
+# Class names that carry a code block's language (Prism, highlight.js, GitHub, Pygments...)
+LANGUAGE_CLASS = re.compile(r"^(?:language|lang|highlight|brush|syntax|hljs)-([\w+#.-]+)$", re.IGNORECASE)
+
+# Language names Zola's highlighter does not know, mapped to ones it does
+CODE_LANGUAGE_ALIASES = {
+    "shell": "bash", "sh": "bash", "zsh": "bash", "console": "bash", "shell-session": "bash", "terminal": "bash",
+    "js": "javascript", "jsx": "javascript", "ts": "typescript", "py": "python", "py3": "python",
+    "yml": "yaml", "golang": "go", "c++": "cpp", "plaintext": "text", "plain": "text", "none": "text",
+    "txt": "text", "html5": "html", "xml": "xml", "ps1": "powershell",
+}
+
+# Characters that would otherwise be read as Markdown syntax inside text
+ESCAPED_CHARS = re.compile(r"([\\`*_\[\]<])")
+# Paragraph starts that would turn into headings, quotes or list items
+LINE_START_MARKER = re.compile(r"^(?:([#>]|[-+=](?=\s))|(\d+)([.)])(?=\s))")
+
+
+class MarkdownConverter:
+    """Converts an HTML fragment to Markdown block by block."""
+
+    def __init__(self, base_url: Optional[str] = None, image_resolver: Optional[ImageResolver] = None):
+        self.base_url = base_url
+        self.image_resolver = image_resolver
+
+    def convert(self, html: str) -> str:
+        """
+        Convert an HTML fragment to Markdown.
+
+        Args:
+            html: Article HTML
+
+        Returns:
+            Markdown text ending in a newline (empty string if nothing remains)
+        """
+        soup = BeautifulSoup(html, "html.parser")
+        markdown = "\n\n".join(self.blocks(soup))
+        markdown = re.sub(r"\n{3,}", "\n\n", markdown).strip()
+        return markdown + "\n" if markdown else ""
+
+    # Block level
+
+    def blocks(self, element) -> List[str]:
+        """Convert an element's children to a list of Markdown blocks."""
+        blocks = []
+        inline = []
+
+        def flush():
+            paragraph = self.clean_inline("".join(inline))
+            if paragraph:
+                blocks.append(self.escape_line_start(paragraph))
+            inline.clear()
+
+        for child in element.children:
+            if isinstance(child, Comment):
+                continue
+            if isinstance(child, NavigableString):
+                inline.append(self.text(child))
+            elif child.name in SKIPPED_TAGS:
+                continue
+            elif child.name in BLOCK_TAGS:
+                flush()
+                blocks.extend(block for block in self.block(child) if block.strip())
+            else:
+                inline.append(self.inline(child))
+        flush()
+        return blocks
+
+    def block(self, el) -> List[str]:
+        """Convert a single block-level element."""
+        name = el.name
+        if name in HEADING_TAGS:
+            text = re.sub(r"\\?\n", " ", self.clean_inline(self.inline_children(el)))
+            return [f"{'#' * int(name[1])} {text}"] if text else []
+        if name == "p":
+            text = self.clean_inline(self.inline_children(el))
+            return [self.escape_line_start(text)] if text else []
+        if name in ("ul", "ol"):
+            return [self.list_block(el)]
+        if name == "pre":
+            return [self.code_block(el)]
+        if name == "blockquote":
+            inner = "\n\n".join(self.blocks(el))
+            return ["\n".join(f"> {line}" if line else ">" for line in inner.splitlines())] if inner else []
+        if name == "table":
+            return [self.table_block(el)]
+        if name == "hr":
+            return ["---"]
+        if name == "figcaption":
+            text = self.clean_inline(self.inline_children(el))
+            return [f"*{text}*"] if text else []
+        if name == "dt":
+            text = self.clean_inline(self.inline_children(el))
+            return [f"**{text}**"] if text else []
+        if name in ("iframe", "video", "audio"):
+            source = el.find("source")
+            src = el.get("src") or (source.get("src") if source else None)
+            if not src:
+                return []
+            label = el.get("title") or {"iframe": "Embedded content", "video": "Video", "audio": "Audio"}[name]
+            return [f"[{self.escape(label)}]({self.link_target(src)})"]
+        # Generic containers (div, section, figure, picture, dd...)
+        return self.blocks(el)
+
+    def list_block(self, el) -> str:
+        """Convert a ``<ul>``/``<ol>``, including nested lists."""
+        ordered = el.name == "ol"
+        try:
+            number = int(el.get("start", 1))
+        except ValueError:
+            number = 1
+
+        items = []
+        for li in el.find_all("li", recursive=False):
+            marker = f"{number}. " if ordered else "- "
+            number += 1
+            parts = self.blocks(li)
+            if not parts:
+                continue
+            # Nested lists stay tight; other block content is separated by a blank line
+            body = parts[0]
+            for part in parts[1:]:
+                separator = "\n" if re.match(r"^(\s*)([-*+]|\d+\.) ", part) else "\n\n"
+                body += separator + part
+            indent = " " * len(marker)
+            lines = body.splitlines()
+            items.append(marker + lines[0] + "".join(
+                f"\n{indent}{line}" if line else "\n" for line in lines[1:]
+            ))
+        return "\n".join(items)
+
+    def code_block(self, pre) -> str:
+        """Convert ``<pre>`` to a fenced code block with a language hint."""
+        code = pre.find("code") or pre
+        language = self.code_language(code) or self.code_language(pre)
+        text = code.get_text().rstrip("\n")
+        # The fence must be longer than any backtick run in the code itself
+        longest = max((len(run) for run in re.findall(r"`+", text)), default=0)
+        fence = "`" * max(3, longest + 1)
+        return f"{fence}{language or ''}\n{text}\n{fence}"
+
+    @staticmethod
+    def code_language(el) -> Optional[str]:
+        """Read a code block's language from its class names or data attributes."""
+        language = el.get("data-lang") or el.get("data-language")
+        if not language:
+            for name in el.get("class") or []:
+                match = LANGUAGE_CLASS.match(name)
+                if match:
+                    language = match.group(1)
+                    break
+        if not language:
+            return None
+        language = language.lower()
+        return CODE_LANGUAGE_ALIASES.get(language, language)
+
+    def table_block(self, table) -> str:
+        """Convert a table to a GitHub-style pipe table (first row is the header)."""
+        rows = []
+        for tr in table.find_all("tr"):
+            if tr.find_parent("table") is not table:
+                continue  # Row of a nested table
+            cells = [
+                re.sub(r"\\?\n", " ", self.clean_inline(self.inline_children(cell))).replace("|", "\\|")
+                for cell in tr.find_all(["th", "td"], recursive=False)
+            ]
+            if any(cells):
+                rows.append(cells)
+        if not rows:
+            return ""
+
+        # Single-column tables are layout, not data
+        width = max(len(row) for row in rows)
+        if width == 1:
+            return "\n\n".join(row[0] for row in rows if row[0])
+
+        rows = [row + [""] * (width - len(row)) for row in rows]
+        lines = ["| " + " | ".join(rows[0]) + " |", "|" + "|".join(" --- " for _ in range(width)) + "|"]
+        lines.extend("| " + " | ".join(row) + " |" for row in rows[1:])
+        return "\n".join(lines)
+
+    # Inline level
+
+    def inline_children(self, el) -> str:
+        """Convert an element's children as inline content."""
+        parts = []
+        for child in el.children:
+            if isinstance(child, Comment):
+                continue
+            if isinstance(child, NavigableString):
+                parts.append(self.text(child))
+            elif child.name in SKIPPED_TAGS:
+                continue
+            elif child.name in ("ul", "ol", "pre", "table", "blockquote"):
+                # Block content inside a paragraph-like element
+                parts.append("\n\n" + "\n\n".join(self.block(child)) + "\n\n")
+            elif child.name in BLOCK_TAGS:
+                parts.append(" " + self.inline_children(child) + " ")
+            else:
+                parts.append(self.inline(child))
+        return "".join(parts)
+
+    def inline(self, el) -> str:
+        """Convert an inline element."""
+        name = el.name
+        if name == "br":
+            return "\\\n"
+        if name == "img":
+            return self.image(el)
+        if name == "a":
+            return self.link(el)
+        if name == "code":
+            return self.inline_code(el.get_text())
+        if name in ("strong", "b"):
+            return self.wrap(self.inline_children(el), "**")
+        if name in ("em", "i", "cite"):
+            return self.wrap(self.inline_children(el), "*")
+        if name in ("del", "s", "strike"):
+            return self.wrap(self.inline_children(el), "~~")
+        return self.inline_children(el)
+
+    def link(self, a) -> str:
+        """Convert a link, dropping in-page anchors and script links."""
+        text = self.inline_children(a)
+        href = (a.get("href") or "").strip()
+        if not href or href.startswith(("#", "javascript:")) or not text.strip():
+            return text
+        lead, core, trail = self.split_space(text)
+        return f"{lead}[{core}]({self.link_target(href)}){trail}"
+
+    def image(self, img) -> str:
+        """Convert an image, letting the resolver localize or drop it."""
+        src = img.get("src") or ""
+        if not src or src.startswith("data:"):
+            # Lazy-loaded images keep the real URL in a data attribute or srcset
+            srcset = img.get("data-srcset") or img.get("srcset") or ""
+            src = img.get("data-src") or img.get("data-original") or srcset.split(",")[0].strip().split(" ")[0]
+        if not src or src.startswith("data:"):
+            return ""
+        src = urljoin(self.base_url, src) if self.base_url else src
+        alt = re.sub(r"\s+", " ", img.get("alt") or "").strip()
+
+        path = self.image_resolver(src, alt) if self.image_resolver else src
+        if not path:
+            return ""
+        return f"![{self.escape(alt)}]({self.link_target(path, absolute=False)})"
+
+    def link_target(self, url: str, absolute: bool = True) -> str:
+        """Resolve a URL against the page and wrap it if it has spaces or parentheses."""
+        if absolute and self.base_url:
+            url = urljoin(self.base_url, url)
+        return f"<{url}>" if re.search(r"[\s()]", url) else url
+
+    @staticmethod
+    def inline_code(text: str) -> str:
+        """Wrap text in enough backticks to contain any backticks it holds."""
+        text = re.sub(r"\s+", " ", text)
+        if not text.strip():
+            return text
+        longest = max((len(run) for run in re.findall(r"`+", text)), default=0)
+        ticks = "`" * (longest + 1)
+        padding = " " if text.startswith("`") or text.endswith("`") else ""
+        return f"{ticks}{padding}{text}{padding}{ticks}"
+
+    def wrap(self, text: str, marker: str) -> str:
+        """Wrap inline text in emphasis markers, keeping surrounding spaces outside them."""
+        lead, core, trail = self.split_space(text)
+        return f"{lead}{marker}{core}{marker}{trail}" if core else text
+
+    @staticmethod
+    def split_space(text: str):
+        core = text.strip()
+        if not core:
+            return text, "", ""
+        lead = " " if text[:1].isspace() else ""
+        trail = " " if text[-1:].isspace() else ""
+        return lead, core, trail
+
+    def text(self, string: str) -> str:
+        """Escape a text node and collapse its whitespace."""
+        return self.escape(re.sub(r"\s+", " ", str(string)))
+
+    @staticmethod
+    def escape(text: str) -> str:
+        return ESCAPED_CHARS.sub(r"\\\1", text)
+
+    @staticmethod
+    def escape_line_start(text: str) -> str:
+        """Keep a paragraph from starting like a heading, quote or list item."""
+        match = LINE_START_MARKER.match(text)
+        if not match:
+            return text
+        if match.group(1):
+            return "\\" + text
+        return f"{match.group(2)}\\{text[len(match.group(2)):]}"
+
+    @staticmethod
+    def clean_inline(text: str) -> str:
+        """Trim spaces around line breaks and the ends of a paragraph."""
+        text = re.sub(r"[ \t]+", " ", text)
+        text = re.sub(r" *\n *", "\n", text)
+        text = re.sub(r"\n{3,}", "\n\n", text)
+        # Line breaks at either end of a paragraph are meaningless
+        return re.sub(r"^(?:\\\n|\s)+|(?:\\\n|\s)+$", "", text)
+
+
+def html_to_markdown(html: str, base_url: Optional[str] = None,
+                     image_resolver: Optional[ImageResolver] = None) -> str:
+    """
+    Convert article HTML to Markdown.
+
+    Args:
+        html: Article HTML (e.g. from ``content_scraper.fetch_content``)
+        base_url: Page URL, used to make relative links and images absolute
+        image_resolver: Hook that maps each image URL to its Markdown path
+            (e.g. a file downloaded into the page bundle); images keep their
+            absolute URL when omitted
+
+    Returns:
+        Markdown text
+    """
+    try:
+        return MarkdownConverter(base_url, image_resolver).convert(html)
+    except Exception as e:
+        logger.warning(f"Markdown conversion failed: {e}")
+        return ""
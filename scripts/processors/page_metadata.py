@@ -0,0 +1,291 @@
+"""
+Page Metadata Module
+Extracts title, publish date, authors, description, canonical URL and site
+name from JSON-LD, OpenGraph, Twitter cards, Dublin Core and <time> elements.
+"""
+
+import json
+import logging
+import re
+from datetime import datetime
+from email.utils import parsedate_to_datetime
+from typing import Any, Dict, Iterable, List, Optional
+from urllib.parse import urljoin, urlparse
+
+from bs4 import BeautifulSoup
+
+logger = logging.getLogger(__name__)
+
+# schema.org types describing the article itself
+ARTICLE_TYPES = {
+    "Article", "NewsArticle", "BlogPosting", "TechArticle", "Report", "ScholarlyArticle",
+    "AnalysisNewsArticle", "OpinionNewsArticle", "ReportageNewsArticle", "LiveBlogPosting",
+    "SocialMediaPosting", "DiscussionForumPosting",
+}
+
+# Sources in order of trust; the first one that has a field wins
+SOURCE_ORDER = ["json-ld", "opengraph", "twitter", "dublin-core", "meta", "time", "html"]
+
+FIELDS = ["title", "date", "modified", "authors", "description", "canonical_url", "source_site", "image"]
+
+DATE_FORMATS = ["%Y-%m-%d", "%Y/%m/%d", "%Y%m%d", "%d.%m.%Y", "%B %d, %Y", "%b %d, %Y", "%d %B %Y", "%d %b %Y"]
+
+
+def parse_date(value: Any) -> Optional[str]:
+    """
+    Normalize a date string from page metadata to ``YYYY-MM-DD``.
+
+    Args:
+        value: ISO 8601, RFC 2822 or a common written date
+
+    Returns:
+        Date string, or None if the value is not a recognizable date
+    """
+    if not isinstance(value, str) or not value.strip():
+        return None
+    value = value.strip()
+    try:
+        return datetime.fromisoformat(value.replace("Z", "+00:00")).strftime("%Y-%m-%d")
+    except ValueError:
+        pass
+    # ISO dates with trailing noise (e.g. "2024-05-01T10:00:00.000-0400")
+    match = re.match(r"(\d{4})-(\d{2})-(\d{2})", value)
+    if match:
+        return "-".join(match.groups())
+    try:
+        return parsedate_to_datetime(value).strftime("%Y-%m-%d")
+    except (TypeError, ValueError, IndexError):
+        pass
+    for date_format in DATE_FORMATS:
+        try:
+            return datetime.strptime(value, date_format).strftime("%Y-%m-%d")
+        except ValueError:
+            continue
+    return None
+
+
+def clean_authors(values: Iterable[Any]) -> List[str]:
+    """Turn author strings, schema.org Person objects and lists into unique names."""
+    names = []
+
+    def add(value):
+        if isinstance(value, list):
+            for item in value:
+                add(item)
+        elif isinstance(value, dict):
+            add(value.get("name"))
+        elif isinstance(value, str):
+            for name in re.split(r"\s*(?:,|;|\band\b|&)\s*", value):
+                name = re.sub(r"^by\s+", "", name.strip(), flags=re.IGNORECASE).strip()
+                # Profile URLs (article:author on Facebook-style markup) are not names
+                if name and "://" not in name and name.lower() not in (n.lower() for n in names):
+                    names.append(name)
+
+    for value in values:
+        add(value)
+    return names
+
+
+def _json_ld_objects(soup: BeautifulSoup) -> List[Dict[str, Any]]:
+    """All JSON-LD objects on the page, with ``@graph`` containers flattened."""
+    objects = []
+    for script in soup.find_all("script", attrs={"type": "application/ld+json"}):
+        raw = script.string or script.get_text()
+        try:
+            data = json.loads(raw, strict=False)
+        except (json.JSONDecodeError, TypeError):
+            logger.debug("Skipping invalid JSON-LD block")
+            continue
+        stack = data if isinstance(data, list) else [data]
+        while stack:
+            item = stack.pop(0)
+            if isinstance(item, list):
+                stack.extend(item)
+            elif isinstance(item, dict):
+                objects.append(item)
+                stack.extend(item.get("@graph", []) if isinstance(item.get("@graph"), list) else [])
+    return objects
+
+
+def _is_article(obj: Dict[str, Any]) -> bool:
+    types = obj.get("@type")
+    types = types if isinstance(types, list) else [types]
+    return any(t in ARTICLE_TYPES for t in types if isinstance(t, str))
+
+
+def from_json_ld(soup: BeautifulSoup) -> Dict[str, Any]:
+    """Read metadata from the page's schema.org Article (or subtype) object."""
+    article = next((obj for obj in _json_ld_objects(soup) if _is_article(obj)), None)
+    if not article:
+        return {}
+
+    canonical = article.get("url")
+    main_entity = article.get("mainEntityOfPage")
+    if not canonical and isinstance(main_entity, dict):
+        canonical = main_entity.get("@id") or main_entity.get("url")
+    elif not canonical and isinstance(main_entity, str):
+        canonical = main_entity
+
+    publisher = article.get("publisher")
+    image = article.get("image")
+    if isinstance(image, list):
+        image = image[0] if image else None
+    if isinstance(image, dict):
+        image = image.get("url")
+
+    return {
+        "title": article.get("headline") or article.get("name"),
+        "date": article.get("datePublished") or article.get("dateCreated"),
+        "modified": article.get("dateModified"),
+        "authors": clean_authors([article.get("author") or article.get("creator")]),
+        "description": article.get("description"),
+        "canonical_url": canonical,
+        "source_site": publisher.get("name") if isinstance(publisher, dict) else None,
+        "image": image,
+    }
+
+
+def _meta(soup: BeautifulSoup, *names: str) -> Optional[str]:
+    """Content of the first ``<meta>`` whose property or name matches (case-insensitive)."""
+    wanted = {name.lower() for name in names}
+    for tag in soup.find_all("meta"):
+        key = (tag.get("property") or tag.get("name") or tag.get("itemprop") or "").lower()
+        if key in wanted and tag.get("content"):
+            return tag["content"].strip()
+    return None
+
+
+def _meta_all(soup: BeautifulSoup, *names: str) -> List[str]:
+    wanted = {name.lower() for name in names}
+    return [tag["content"].strip() for tag in soup.find_all("meta")
+            if (tag.get("property") or tag.get("name") or "").lower() in wanted and tag.get("content")]
+
+
+def from_opengraph(soup: BeautifulSoup) -> Dict[str, Any]:
+    return {
+        "title": _meta(soup, "og:title"),
+        "date": _meta(soup, "article:published_time", "og:published_time"),
+        "modified": _meta(soup, "article:modified_time", "og:updated_time"),
+        "authors": clean_authors(_meta_all(soup, "article:author")),
+        "description": _meta(soup, "og:description"),
+        "canonical_url": _meta(soup, "og:url"),
+        "source_site": _meta(soup, "og:site_name"),
+        "image": _meta(soup, "og:image", "og:image:url", "og:image:secure_url"),
+    }
+
+
+def from_twitter(soup: BeautifulSoup) -> Dict[str, Any]:
+    return {
+        "title": _meta(soup, "twitter:title"),
+        "description": _meta(soup, "twitter:description"),
+        "image": _meta(soup, "twitter:image", "twitter:image:src"),
+    }
+
+
+def from_dublin_core(soup: BeautifulSoup) -> Dict[str, Any]:
+    return {
+        "title": _meta(soup, "dc.title", "dcterms.title"),
+        "date": _meta(soup, "dc.date.issued", "dcterms.issued", "dc.date", "dcterms.date", "dcterms.created"),
+        "modified": _meta(soup, "dcterms.modified", "dc.date.modified"),
+        "authors": clean_authors(_meta_all(soup, "dc.creator", "dcterms.creator")),
+        "description": _meta(soup, "dc.description", "dcterms.description", "dcterms.abstract"),
+        "source_site": _meta(soup, "dc.publisher", "dcterms.publisher"),
+    }
+
+
+def from_meta(soup: BeautifulSoup) -> Dict[str, Any]:
+    """Plain ``<meta name=...>`` tags and ``<link rel=canonical>``."""
+    canonical = None
+    for link in soup.find_all("link"):
+        rel = link.get("rel") or []
+        if "canonical" in (rel if isinstance(rel, list) else rel.split()) and link.get("href"):
+            canonical = link["href"].strip()
+            break
+    return {
+        "date": _meta(soup, "datePublished", "date", "pubdate", "publish-date", "publish_date",
+                      "article.published", "parsely-pub-date", "sailthru.date"),
+        "modified": _meta(soup, "dateModified", "last-modified"),
+        "authors": clean_authors(_meta_all(soup, "author", "parsely-author", "sailthru.author")),
+        "description": _meta(soup, "description"),
+        "canonical_url": canonical,
+        "source_site": _meta(soup, "application-name", "apple-mobile-web-app-title"),
+    }
+
+
+def from_time_elements(soup: BeautifulSoup) -> Dict[str, Any]:
+    """Publish date from ``<time>``: explicitly marked ones first, then the first in the article."""
+    candidates = soup.find_all("time", attrs={"itemprop": "datePublished"}) + soup.find_all("time", attrs={"pubdate": True})
+    container = soup.find("article") or soup.find("main") or soup
+    candidates += container.find_all("time")
+    for time_tag in candidates:
+        value = time_tag.get("datetime") or time_tag.get_text(" ", strip=True)
+        if parse_date(value):
+            return {"date": value}
+    return {}
+
+
+def from_html(soup: BeautifulSoup) -> Dict[str, Any]:
+    title_tag = soup.find("title")
+    h1_tag = soup.find("h1")
+    title = (title_tag.get_text(strip=True) if title_tag else "") or (h1_tag.get_text(strip=True) if h1_tag else "")
+    return {"title": title or None}
+
+
+EXTRACTORS = {
+    "json-ld": from_json_ld,
+    "opengraph": from_opengraph,
+    "twitter": from_twitter,
+    "dublin-core": from_dublin_core,
+    "meta": from_meta,
+    "time": from_time_elements,
+    "html": from_html,
+}
+
+
+def extract_page_metadata(soup: BeautifulSoup, url: Optional[str] = None) -> Dict[str, Any]:
+    """
+    Merge page metadata from every source, most trusted first.
+
+    Args:
+        soup: Parsed page
+        url: Page URL (resolves relative canonical/image URLs, fallback site name)
+
+    Returns:
+        Dictionary with ``title``, ``date``, ``modified`` (``YYYY-MM-DD``),
+        ``authors`` (list), ``description``, ``canonical_url``,
+        ``source_site``, ``image``, ``lang`` and ``sources`` (field -> source
+        the value came from); missing fields are None
+    """
+    result: Dict[str, Any] = {field: None for field in FIELDS}
+    result["authors"] = []
+    sources: Dict[str, str] = {}
+
+    for source in SOURCE_ORDER:
+        try:
+            found = EXTRACTORS[source](soup)
+        except Exception as e:
+            logger.warning(f"Could not read {source} metadata: {e}")
+            continue
+        for field, value in found.items():
+            if field in ("date", "modified"):
+                value = parse_date(value)
+            elif isinstance(value, str):
+                value = re.sub(r"\s+", " ", value).strip() or None
+            if value and not result.get(field):
+                result[field] = value
+                sources[field] = source
+
+    for field in ("canonical_url", "image"):
+        if result[field] and url:
+            result[field] = urljoin(url, result[field])
+
+    if not result["source_site"] and url:
+        host = urlparse(url).netloc.split(":")[0]
+        result["source_site"] = host[4:] if host.startswith("www.") else host
+        sources["source_site"] = "url"
+
+    html_tag = soup.find("html")
+    lang = html_tag.get("lang") if html_tag else None
+    result["lang"] = lang.split("-")[0].lower() if lang else None
+    result["sources"] = sources
+    return result
@@ -0,0 +1,215 @@
+"""
+Slugs Module
+Bundle folder names and stable post IDs: Unicode-aware slugs with optional
+transliteration, a length limit, and collision detection against existing
+bundles.
+"""
+
+import base64
+import hashlib
+import logging
+import re
+import unicodedata
+from pathlib import Path
+from typing import Optional, Set
+from urllib.parse import parse_qsl, unquote, urlencode, urlparse, urlunparse
+
+from .bundle_schema import METADATA_FILE, load_bundle_source
+from .config import bundle_dirs, settings
+
+logger = logging.getLogger(__name__)
+
+# Path segments that say nothing about the page
+GENERIC_SEGMENTS = {"index", "default", "home", "post", "posts", "article", "articles", "blog", "news",
+                    "story", "entry", "amp", "p"}
+PAGE_EXTENSION = re.compile(r"\.(?:html?|php|aspx?|jsp|cfm|shtml)$", re.IGNORECASE)
+# Numeric or hex IDs, which need the segment before them (or the title) to mean anything
+ID_SEGMENT = re.compile(r"[\d_-]+|[0-9a-f]{8,}", re.IGNORECASE)
+CJK = re.compile(r"[\u3400-\u4dbf\u4e00-\u9fff\uf900-\ufaff]")
+TRACKING_PARAMS = re.compile(r"^(?:utm_\w+|fbclid|gclid|mc_cid|mc_eid|ref|ref_src|source)$", re.IGNORECASE)
+
+# Zola alias that always redirects to the post, whatever its folder is called
+ALIAS_TEMPLATE = "/p/{id}/"
+
+
+def transliterate(text: str) -> str:
+    """
+    Romanize text for ASCII slugs: pinyin for Chinese, accents stripped elsewhere.
+
+    Chinese needs ``pypinyin``; without it Chinese characters are kept as they are.
+    """
+    if CJK.search(text):
+        try:
+            from pypinyin import lazy_pinyin
+            text = " ".join(lazy_pinyin(text))
+        except ImportError:
+            logger.warning("⚠️ Install pypinyin to transliterate Chinese slugs (pip install pypinyin)")
+    decomposed = unicodedata.normalize("NFKD", text)
+    return "".join(ch for ch in decomposed if not unicodedata.combining(ch))
+
+
+def make_slug(text: str, max_length: Optional[int] = None, romanize: Optional[bool] = None) -> str:
+    """
+    Create a slug from a title, keeping letters of any script.
+
+    Args:
+        text: Title or other text
+        max_length: Maximum length (``slugs.max_length`` if None); cut at a word boundary
+        romanize: Transliterate to ASCII (``slugs.transliterate`` if None)
+
+    Returns:
+        Lowercase slug of letters, digits and hyphens (empty if the text has none)
+    """
+    max_length = max_length or settings.slug_max_length
+    romanize = settings.slug_transliterate if romanize is None else romanize
+
+    text = unicodedata.normalize("NFKC", text)
+    if romanize:
+        text = transliterate(text)
+    slug = re.sub(r"[\W_]+", "-", text.lower()).strip("-")
+
+    if len(slug) > max_length:
+        cut = slug[:max_length]
+        # Prefer ending on a whole word unless that throws away more than half
+        if slug[max_length] != "-" and cut.rfind("-") > max_length // 2:
+            cut = cut[:cut.rfind("-")]
+        slug = cut.strip("-")
+    return slug
+
+
+def slug_from_url(url: str, title: Optional[str] = None) -> str:
+    """
+    Create a slug for a web page from the meaningful end of its URL path.
+
+    ``index.html``-style and numeric-only segments are skipped in favour of
+    the title, so that pages of different sites do not share a slug.
+
+    Args:
+        url: Page URL
+        title: Page title, used when the path says nothing about the page
+
+    Returns:
+        Slug (the host name if nothing else is usable)
+    """
+    parsed = urlparse(url)
+    segments = [PAGE_EXTENSION.sub("", unquote(s)) for s in parsed.path.split("/") if s]
+    segments = [s for s in segments if s and s.lower() not in GENERIC_SEGMENTS]
+
+    candidate = ""
+    if segments:
+        candidate = segments[-1]
+        if ID_SEGMENT.fullmatch(candidate):
+            candidate = title or (f"{segments[-2]}-{candidate}" if len(segments) > 1 else candidate)
+    elif title:
+        candidate = title
+
+    host = parsed.netloc.split(":")[0]
+    return make_slug(candidate) or make_slug(host[4:] if host.startswith("www.") else host) or "web-content"
+
+
+def normalize_url(url: Optional[str]) -> str:
+    """Normalize a source URL for comparison (host case, www., tracking params, trailing slash)."""
+    if not url:
+        return ""
+    parsed = urlparse(url.strip())
+    host = parsed.netloc.lower()
+    host = host[4:] if host.startswith("www.") else host
+    query = urlencode(sorted((k, v) for k, v in parse_qsl(parsed.query) if not TRACKING_PARAMS.match(k)))
+    return urlunparse((parsed.scheme.lower() or "https", host, parsed.path.rstrip("/") or "/", "", query, ""))
+
+
+def post_id(url: Optional[str] = None, video_id: Optional[str] = None) -> str:
+    """
+    Stable short ID of a post, derived from its source.
+
+    The same source always gets the same ID, so re-imports and renamed
+    bundles keep their ``/p/<id>/`` alias.
+
+    Args:
+        url: Source URL (web posts)
+        video_id: YouTube video ID (YouTube posts)
+
+    Returns:
+        8-character lowercase base32 ID
+    """
+    source = f"youtube:{video_id}" if video_id else normalize_url(url)
+    digest = hashlib.sha256(source.encode("utf-8")).digest()
+    return base64.b32encode(digest).decode("ascii")[:8].lower()
+
+
+def post_alias(pid: str) -> str:
+    """Zola alias path for a post ID."""
+    return ALIAS_TEMPLATE.format(id=pid)
+
+
+def find_bundle_by_source(url: Optional[str] = None, video_id: Optional[str] = None) -> Optional[Path]:
+    """
+    Find the bundle imported from a source URL or YouTube video.
+
+    Args:
+        url: Source URL (compared after ``normalize_url``)
+        video_id: YouTube video ID (``source.id`` of YouTube bundles)
+
+    Returns:
+        Bundle directory, or None if the source was not imported yet
+    """
+    wanted = normalize_url(url) if url else None
+    for post_dir in bundle_dirs(predicate=lambda d: (d / METADATA_FILE).exists()):
+        try:
+            source = load_bundle_source(post_dir) or {}
+        except (OSError, ValueError) as e:
+            logger.debug(f"Skipping unreadable {post_dir / METADATA_FILE}: {e}")
+            continue
+        if video_id and source.get("type") == "youtube" and source.get("id") == video_id:
+            return post_dir
+        if wanted and normalize_url(source.get("url")) == wanted:
+            return post_dir
+    return None
+
+
+def imported_youtube_ids() -> Set[str]:
+    """IDs of every YouTube video that already has a bundle."""
+    ids = set()
+    for post_dir in bundle_dirs(predicate=lambda d: (d / METADATA_FILE).exists()):
+        try:
+            source = load_bundle_source(post_dir) or {}
+        except (OSError, ValueError):
+            continue
+        if source.get("type") == "youtube" and source.get("id"):
+            ids.add(source["id"])
+    return ids
+
+
+def unique_slug(base: str, url: Optional[str] = None, video_id: Optional[str] = None,
+                section: Optional[str] = None) -> str:
+    """
+    Pick the bundle folder for a source.
+
+    Re-imports of a source reuse its existing bundle (found by the source in
+    asset.json, even if the folder was renamed). Otherwise ``base`` gets a
+    numeric suffix when another source's bundle already uses it.
+
+    Args:
+        base: Preferred slug
+        url: Source URL
+        video_id: YouTube video ID
+        section: Series section folder to create new bundles in
+
+    Returns:
+        Bundle path relative to the content root (``section/slug`` in a section)
+    """
+    existing = find_bundle_by_source(url=None if video_id else url, video_id=video_id)
+    if existing:
+        slug = existing.relative_to(settings.content_root).as_posix()
+        logger.info(f"♻️ Source already imported as {slug}")
+        return slug
+
+    parent = settings.content_root / section if section else settings.content_root
+    slug, n = base, 2
+    while (parent / slug).exists():
+        suffix = f"-{n}"
+        slug = base[:settings.slug_max_length - len(suffix)].rstrip("-") + suffix
+        n += 1
+    if slug != base:
+        logger.warning(f"⚠️ Bundle '{base}' belongs to another source, using '{slug}'")
+    return f"{section}/{slug}" if section else slug
@@ -4,60 +4,201 @@ Handles text-to-speech conversion, audio processing, and voice synthesis.
 """
 
 import asyncio
+import json
 import logging
+import os
+import re
+import shutil
 from pathlib import Path
-from typing import List
+from typing import Any, Dict, List, Optional
 
 import edge_tts
 import subprocess
 
+from .cache import cache_key, step_cache
+from .config import settings
+from .captions import attach_punctuation, boundary_from_event, offset_boundaries, write_webvtt
+
 logger = logging.getLogger(__name__)
 
 
-async def text_to_speech_chunks(chunks: List[str], temp_folder: Path, voice: str = "en-US-AriaNeural") -> List[Path]:
+# Sentence terminators for Latin and CJK text. Latin terminators must be
+# followed by whitespace (so "3.14" stays intact); CJK punctuation is not
+# followed by whitespace, so the split happens directly after it (plus any
+# closing quotes or brackets).
+SENTENCE_END_PATTERN = re.compile(
+    r'(?<=[.!?;…])["\'”’)\]]*(?:\s+|$)'
+    r'|(?<=[。！？；])[”’）」』]*\s*'
+)
+CJK_PATTERN = re.compile(r'[\u3040-\u30ff\u3400-\u4dbf\u4e00-\u9fff\uac00-\ud7af]')
+
+DEFAULT_MAX_CHARS = 1500
+# Gemini TTS input limit per request; longer text is split (see core/gemini_tts.py)
+GEMINI_MAX_CHARS = int(os.getenv("GEMINI_MAX_CHARS", "4000"))
+DEFAULT_MAX_WORKERS = 4
+
+# Mastering chain applied to generated narration (set AUDIO_MASTERING=0 to disable)
+MASTERING_ENABLED = os.getenv("AUDIO_MASTERING", "1") != "0"
+MASTERING_TARGET_LUFS = float(os.getenv("AUDIO_TARGET_LUFS", "-16"))
+MASTERING_TRUE_PEAK = -1.5
+MASTERING_LRA = 11.0
+MASTERING_FADE_SECONDS = 0.5
+MASTERING_SAMPLE_RATE = 44100
+# Silence between stitched chunks, for every backend that splits long text
+CHUNK_PAUSE_SECONDS = float(os.getenv("AUDIO_CHUNK_PAUSE", "0.4"))
+
+
+async def edge_tts_to_file(text: str, output_file: Path, voice: str = "en-US-AriaNeural") -> List[Dict[str, Any]]:
+    """
+    Synthesize text with Edge TTS and collect its word timings.
+
+    Args:
+        text: Text to convert
+        output_file: MP3 file to write
+        voice: TTS voice to use
+
+    Returns:
+        Word boundaries (seconds, relative to the start of this file)
+    """
+    communicate = edge_tts.Communicate(text, voice, boundary="WordBoundary")
+    boundaries = []
+    with open(output_file, "wb") as f:
+        async for event in communicate.stream():
+            if event["type"] == "audio":
+                f.write(event["data"])
+            elif event["type"] in ("WordBoundary", "SentenceBoundary"):
+                boundaries.append(boundary_from_event(event))
+    return attach_punctuation(boundaries, text)
+
+
+async def text_to_speech_chunks(chunks: List[str], temp_folder: Path, voice: str = "en-US-AriaNeural",
+                                max_workers: int = DEFAULT_MAX_WORKERS,
+                                boundaries: Optional[List[List[Dict[str, Any]]]] = None) -> List[Path]:
     """
     Convert text chunks to speech using Edge TTS.
 
+    Chunks are synthesized concurrently, bounded by ``max_workers``. Each chunk
+    is written to its own indexed file so the returned list is always in the
+    same order as ``chunks``.
+
     Args:
         chunks: List of text chunks to convert
         temp_folder: Directory to store temporary audio files
         voice: TTS voice to use
+        max_workers: Maximum number of chunks synthesized at the same time
+        boundaries: Optional list that receives each chunk's word boundaries,
+            in chunk order
 
     Returns:
-        List of paths to generated audio files
+        List of paths to generated audio files, in chunk order
     """
-    mp3_files = []
-    for idx, chunk in enumerate(chunks):
-        mp3_path = temp_folder / "03d"
-        mp3_files.append(mp3_path)
-        logger.info(f"Converting chunk {idx+1}/{len(chunks)} to speech...")
-        try:
-            communicate = edge_tts.Communicate(chunk, voice)
-            await communicate.save(str(mp3_path))
-        except Exception as e:
-            logger.error(f"Failed to convert chunk {idx+1}: {e}")
-            raise
+    temp_folder.mkdir(parents=True, exist_ok=True)
+    semaphore = asyncio.Semaphore(max(1, max_workers))
+    mp3_files = [temp_folder / f"chunk_{idx:03d}.mp3" for idx in range(len(chunks))]
+    chunk_boundaries: List[List[Dict[str, Any]]] = [[] for _ in chunks]
+
+    async def convert(idx: int, chunk: str, mp3_path: Path) -> None:
+        async with semaphore:
+            logger.info(f"Converting chunk {idx+1}/{len(chunks)} to speech...")
+            try:
+                chunk_boundaries[idx] = await edge_tts_to_file(chunk, mp3_path, voice)
+            except Exception as e:
+                logger.error(f"Failed to convert chunk {idx+1}: {e}")
+                raise
+
+    await asyncio.gather(*(
+        convert(idx, chunk, mp3_path)
+        for idx, (chunk, mp3_path) in enumerate(zip(chunks, mp3_files))
+    ))
+    if boundaries is not None:
+        boundaries.extend(chunk_boundaries)
     return mp3_files
 
 
-def combine_mp3(mp3_files: List[Path], output_file: Path) -> None:
+def probe_audio(audio_file: Path) -> Dict[str, Any]:
+    """
+    Read duration, sample rate and channel count of an audio file with ffprobe.
+
+    Args:
+        audio_file: Path to audio file
+
+    Returns:
+        Dictionary with ``duration`` (seconds), ``sample_rate`` and ``channels``
+    """
+    result = subprocess.run([
+        "ffprobe", "-v", "quiet", "-print_format", "json",
+        "-show_format", "-show_streams", str(audio_file)
+    ], capture_output=True, text=True, check=True)
+    data = json.loads(result.stdout)
+    stream = next((s for s in data.get("streams", []) if s.get("codec_type") == "audio"), {})
+    return {
+        "duration": float(data.get("format", {}).get("duration", 0.0)),
+        "sample_rate": int(stream.get("sample_rate", 24000)),
+        "channels": int(stream.get("channels", 1)),
+    }
+
+
+def get_audio_duration(audio_file: Path) -> float:
+    """
+    Get the duration of an audio file in seconds.
+
+    Args:
+        audio_file: Path to audio file
+
+    Returns:
+        Duration in seconds (0.0 if it cannot be determined)
+    """
+    try:
+        return probe_audio(audio_file)["duration"]
+    except Exception as e:
+        logger.warning(f"Could not read duration of {audio_file}: {e}")
+        return 0.0
+
+
+def _make_silence(reference: Path, seconds: float, output_file: Path) -> None:
+    """Write an MP3 of silence matching the reference file's sample rate and channels."""
+    info = probe_audio(reference)
+    layout = "mono" if info["channels"] == 1 else "stereo"
+    subprocess.run([
+        "ffmpeg", "-y", "-f", "lavfi",
+        "-i", f"anullsrc=r={info['sample_rate']}:cl={layout}",
+        "-t", f"{seconds:.3f}",
+        "-codec:a", "libmp3lame", "-b:a", "48k",
+        str(output_file)
+    ], check=True, capture_output=True, text=True)
+
+
+def _concat_entry(audio_file: Path) -> str:
+    """Concat demuxer list line for a file, with single quotes escaped."""
+    escaped = str(audio_file.resolve()).replace("'", "'\\''")
+    return f"file '{escaped}'\n"
+
+
+def combine_mp3(mp3_files: List[Path], output_file: Path, pause_seconds: float = 0.0) -> None:
     """
     Combine multiple MP3 files into a single audio file using FFmpeg.
 
     Args:
-        mp3_files: List of MP3 file paths to combine
+        mp3_files: List of MP3 file paths to combine, in playback order
         output_file: Output file path
+        pause_seconds: Silence inserted between consecutive chunks
     """
     logger.info(f"Combining {len(mp3_files)} audio chunks...")
 
     # Create concat list file
     temp_folder = mp3_files[0].parent
     concat_list = temp_folder / "concat_list.txt"
+    silence_file = temp_folder / "pause.mp3"
 
     try:
-        with open(concat_list, "w") as f:
-            for mp3 in mp3_files:
-                f.write(f"file '{mp3.resolve()}'\n")
+        if pause_seconds > 0 and len(mp3_files) > 1:
+            _make_silence(mp3_files[0], pause_seconds, silence_file)
+
+        with open(concat_list, "w", encoding="utf-8") as f:
+            for index, mp3 in enumerate(mp3_files):
+                if index > 0 and silence_file.exists():
+                    f.write(_concat_entry(silence_file))
+                f.write(_concat_entry(mp3))
 
         # Run FFmpeg concatenation
         cmd = [
@@ -80,71 +221,704 @@ def combine_mp3(mp3_files: List[Path], output_file: Path) -> None:
         logger.error(f"Audio combination failed: {e}")
         raise
     finally:
-        # Clean up concat list
-        if concat_list.exists():
-            concat_list.unlink()
+        # Clean up concat list and pause file
+        for temp_file in (concat_list, silence_file):
+            if temp_file.exists():
+                temp_file.unlink()
+
 
+def _measure_loudness(audio_file: Path, target_lufs: float, true_peak: float, lra: float) -> Dict[str, str]:
+    """First loudnorm pass: measure integrated loudness, true peak, LRA and threshold."""
+    result = subprocess.run([
+        "ffmpeg", "-hide_banner", "-nostats", "-i", str(audio_file),
+        "-af", f"loudnorm=I={target_lufs}:TP={true_peak}:LRA={lra}:print_format=json",
+        "-f", "null", "-"
+    ], capture_output=True, text=True, check=True)
 
-def split_text_into_chunks(text: str, max_words: int = 250) -> List[str]:
+    # loudnorm prints its JSON report at the end of stderr
+    report = result.stderr[result.stderr.rindex("{"):result.stderr.rindex("}") + 1]
+    return json.loads(report)
+
+
+def master_audio(audio_file: Path, target_lufs: float = MASTERING_TARGET_LUFS, true_peak: float = MASTERING_TRUE_PEAK,
+                 lra: float = MASTERING_LRA, trim_silence: bool = True, fade_seconds: float = MASTERING_FADE_SECONDS) -> bool:
+    """
+    Master a narration file in place for podcast playback.
+
+    Trims leading/trailing silence, applies two-pass EBU R128 loudness
+    normalization to ``target_lufs`` and adds a fade in and out.
+
+    Args:
+        audio_file: Audio file to master (overwritten on success)
+        target_lufs: Integrated loudness target (-16 LUFS for podcasts)
+        true_peak: Maximum true peak in dBTP
+        lra: Target loudness range
+        trim_silence: Remove silence at the start and end
+        fade_seconds: Length of the fade in and fade out
+
+    Returns:
+        True if mastering was applied, False if it was skipped or failed
+    """
+    logger.info(f"🎚️ Mastering {audio_file.name} to {target_lufs} LUFS...")
+    trimmed_file = audio_file.with_name(f"{audio_file.stem}.trimmed.wav")
+    mastered_file = audio_file.with_name(f"{audio_file.stem}.mastered{audio_file.suffix}")
+
+    try:
+        # 1. Trim silence at both ends (reverse trick handles the tail)
+        source = audio_file
+        if trim_silence:
+            trim = "silenceremove=start_periods=1:start_threshold=-50dB:start_silence=0.1"
+            subprocess.run([
+                "ffmpeg", "-y", "-i", str(audio_file),
+                "-af", f"{trim},areverse,{trim},areverse",
+                str(trimmed_file)
+            ], check=True, capture_output=True, text=True)
+            source = trimmed_file
+
+        # 2. Measure loudness (first loudnorm pass)
+        measured = _measure_loudness(source, target_lufs, true_peak, lra)
+        duration = get_audio_duration(source)
+
+        # 3. Normalize with the measured values (second pass) and fade
+        filters = [
+            f"loudnorm=I={target_lufs}:TP={true_peak}:LRA={lra}"
+            f":measured_I={measured['input_i']}:measured_TP={measured['input_tp']}"
+            f":measured_LRA={measured['input_lra']}:measured_thresh={measured['input_thresh']}"
+            f":offset={measured['target_offset']}:linear=true",
+        ]
+        if fade_seconds > 0 and duration > fade_seconds * 4:
+            filters.append(f"afade=t=in:st=0:d={fade_seconds}")
+            filters.append(f"afade=t=out:st={duration - fade_seconds:.3f}:d={fade_seconds}")
+
+        cmd = ["ffmpeg", "-y", "-i", str(source), "-af", ",".join(filters), "-ar", str(MASTERING_SAMPLE_RATE)]
+        if audio_file.suffix.lower() == ".mp3":
+            cmd += ["-codec:a", "libmp3lame", "-b:a", "128k"]
+        cmd.append(str(mastered_file))
+        subprocess.run(cmd, check=True, capture_output=True, text=True)
+
+        mastered_file.replace(audio_file)
+        logger.info(f"✅ Mastered {audio_file.name} (measured {measured['input_i']} LUFS)")
+        return True
+
+    except (subprocess.CalledProcessError, FileNotFoundError, ValueError, KeyError) as e:
+        stderr = getattr(e, "stderr", None) or e
+        logger.warning(f"⚠️ Mastering skipped for {audio_file.name}: {stderr}")
+        return False
+    finally:
+        for temp_file in (trimmed_file, mastered_file):
+            if temp_file.exists():
+                temp_file.unlink()
+
+
+def split_into_sentences(text: str) -> List[str]:
+    """
+    Split a paragraph into sentences on Latin and CJK terminators.
+
+    Args:
+        text: Paragraph text
+
+    Returns:
+        List of sentences with their terminating punctuation kept
+    """
+    sentences = []
+    start = 0
+    for match in SENTENCE_END_PATTERN.finditer(text):
+        end = match.end()
+        if end <= start:
+            continue
+        sentence = text[start:end].strip()
+        if sentence:
+            sentences.append(sentence)
+        start = end
+    tail = text[start:].strip()
+    if tail:
+        sentences.append(tail)
+    return sentences
+
+
+def _join_parts(parts: List[str]) -> str:
+    """Join sentences, only inserting a space between Latin-script neighbours."""
+    joined = ""
+    for part in parts:
+        if joined and not (CJK_PATTERN.search(joined[-1]) or CJK_PATTERN.search(part[0])):
+            joined += " "
+        joined += part
+    return joined
+
+
+def _split_oversized(sentence: str, max_chars: int) -> List[str]:
+    """Hard-split a single sentence that exceeds the chunk budget."""
+    if CJK_PATTERN.search(sentence) and " " not in sentence.strip():
+        # No word breaks to respect: cut on character count
+        return [sentence[i:i + max_chars] for i in range(0, len(sentence), max_chars)]
+
+    pieces = []
+    current = []
+    for word in sentence.split():
+        candidate = " ".join(current + [word])
+        if current and len(candidate) > max_chars:
+            pieces.append(" ".join(current))
+            current = [word]
+        else:
+            current.append(word)
+    if current:
+        pieces.append(" ".join(current))
+    return pieces
+
+
+def split_text_into_chunks(text: str, max_chars: int = DEFAULT_MAX_CHARS) -> List[str]:
     """
     Split text into chunks for TTS processing.
 
+    Chunks are built from whole sentences and never cross a paragraph boundary
+    unless the paragraph is short enough to share a chunk with its neighbour.
+    Sentences longer than ``max_chars`` are split on words, or on characters
+    for CJK text without spaces.
+
     Args:
         text: Input text to split
-        max_words: Maximum words per chunk
+        max_chars: Maximum characters per chunk
 
     Returns:
         List of text chunks
     """
-    words = text.split()
     chunks = []
+    current = []
+    current_len = 0
+
+    def flush() -> None:
+        nonlocal current, current_len
+        if current:
+            chunk = _join_parts(current).strip()
+            if chunk:
+                chunks.append(chunk)
+        current = []
+        current_len = 0
+
+    paragraphs = [p.strip() for p in re.split(r'\n\s*\n|\n', text) if p.strip()]
+    for paragraph in paragraphs:
+        sentences = []
+        for sentence in split_into_sentences(paragraph):
+            if len(sentence) > max_chars:
+                sentences.extend(_split_oversized(sentence, max_chars))
+            else:
+                sentences.append(sentence)
 
-    for i in range(0, len(words), max_words):
-        chunk = " ".join(words[i:i + max_words])
-        if chunk.strip():  # Only add non-empty chunks
-            chunks.append(chunk)
+        # Start a new chunk at the paragraph break if the paragraph won't fit
+        if current and current_len + len(paragraph) > max_chars:
+            flush()
 
-    logger.info(f"Split text into {len(chunks)} chunks (max {max_words} words each)")
+        for sentence in sentences:
+            if current and current_len + len(sentence) + 1 > max_chars:
+                flush()
+            current.append(sentence)
+            current_len += len(sentence) + 1
+
+        # Keep a paragraph pause between paragraphs that share a chunk
+        if current:
+            current[-1] = current[-1] + "\n"
+
+    flush()
+
+    logger.info(f"Split text into {len(chunks)} chunks (max {max_chars} characters each)")
     return chunks
 
 
-async def generate_audio_from_text(text: str, output_file: Path, voice: str = "en-US-AriaNeural", temp_dir: Path = None) -> None:
+def convert_audio(input_file: Path, output_file: Path) -> None:
     """
-    Generate audio from text using Edge TTS.
+    Convert an audio file to the format implied by ``output_file``'s suffix.
 
     Args:
-        text: Text to convert to speech
-        output_file: Output audio file path
-        voice: TTS voice to use
-        temp_dir: Temporary directory for chunk processing
+        input_file: Source audio file (e.g. WAV from Gemini)
+        output_file: Destination file (e.g. ``narration.mp3``)
     """
-    if temp_dir is None:
-        temp_dir = output_file.parent / "tmp"
-    temp_dir.mkdir(parents=True, exist_ok=True)
+    cmd = ["ffmpeg", "-y", "-i", str(input_file), "-vn"]
+    if output_file.suffix.lower() == ".mp3":
+        cmd += ["-codec:a", "libmp3lame", "-q:a", "2"]
+    cmd.append(str(output_file))
 
     try:
-        # Split text into manageable chunks
-        chunks = split_text_into_chunks(text)
-
-        if len(chunks) == 1:
-            # Single chunk - direct conversion
-            logger.info("Single chunk - direct TTS conversion")
-            communicate = edge_tts.Communicate(text, voice)
-            await communicate.save(str(output_file))
-        else:
-            # Multiple chunks - process and combine
-            logger.info(f"Multiple chunks ({len(chunks)}) - processing with combination")
-            temp_mp3s = await text_to_speech_chunks(chunks, temp_dir, voice)
-            combine_mp3(temp_mp3s, output_file)
+        subprocess.run(cmd, check=True, capture_output=True, text=True)
+    except subprocess.CalledProcessError as e:
+        logger.error(f"FFmpeg conversion failed: {e.stderr}")
+        raise
+
+
+class TTSBackend:
+    """
+    Common interface for text-to-speech providers.
+
+    Subclasses implement ``synthesize`` and ``list_voices``; the rest of the
+    pipeline only talks to backends through ``generate_audio_from_text``.
+    """
+
+    name = "base"
+    default_voice: Optional[str] = None
+
+    async def synthesize(self, text: str, output_file: Path, voice: Optional[str] = None,
+                         temp_dir: Optional[Path] = None) -> Path:
+        """
+        Synthesize ``text`` into ``output_file``.
+
+        Args:
+            text: Text to convert to speech
+            output_file: Output audio file path
+            voice: Provider-specific voice name (backend default if None)
+            temp_dir: Directory for intermediate files
+
+        Returns:
+            Path to the generated audio file
+        """
+        raise NotImplementedError
+
+    def list_voices(self) -> List[str]:
+        """Return the voice names this backend accepts."""
+        raise NotImplementedError
+
+    def capabilities(self) -> Dict[str, Any]:
+        """
+        Describe what this backend supports.
+
+        Returns:
+            Dictionary with ``max_chars`` per request (None if unlimited),
+            ``concurrent`` synthesis, word-timed ``captions``,
+            ``requires_network``, native ``output_format`` and ``languages``
+        """
+        return {
+            "max_chars": DEFAULT_MAX_CHARS,
+            "concurrent": False,
+            "captions": False,
+            "requires_network": True,
+            "output_format": "mp3",
+            "languages": [],
+        }
+
+
+class EdgeTTSBackend(TTSBackend):
+    """Microsoft Edge neural voices via ``edge-tts``."""
+
+    name = "edge"
+    default_voice = "en-US-AriaNeural"
+
+    def __init__(self, max_workers: int = DEFAULT_MAX_WORKERS):
+        self.max_workers = max_workers
+
+    async def synthesize(self, text: str, output_file: Path, voice: Optional[str] = None,
+                         temp_dir: Optional[Path] = None, captions_file: Optional[Path] = None) -> Path:
+        """
+        Synthesize ``text`` to ``output_file``.
+
+        If ``captions_file`` is given, the word timings of every chunk are
+        shifted by the duration of the audio (and pauses) before it and
+        written as WebVTT.
+        """
+        voice = voice or self.default_voice
+        if temp_dir is None:
+            temp_dir = output_file.parent / "tmp"
+        temp_dir.mkdir(parents=True, exist_ok=True)
+
+        temp_mp3s = []
+        boundaries = []
+        try:
+            # Split text into manageable chunks
+            chunks = split_text_into_chunks(text, self.capabilities()["max_chars"])
 
+            if not chunks:
+                raise ValueError("No text to convert to speech")
+
+            if len(chunks) == 1:
+                # Single chunk - direct conversion
+                logger.info("Single chunk - direct TTS conversion")
+                boundaries = await edge_tts_to_file(chunks[0], output_file, voice)
+            else:
+                # Multiple chunks - process and combine
+                logger.info(f"Multiple chunks ({len(chunks)}) - processing with combination")
+                chunk_boundaries = []
+                temp_mp3s = await text_to_speech_chunks(chunks, temp_dir, voice, self.max_workers, chunk_boundaries)
+                if captions_file:
+                    offset = 0.0
+                    for mp3, words in zip(temp_mp3s, chunk_boundaries):
+                        boundaries.extend(offset_boundaries(words, offset))
+                        offset += get_audio_duration(mp3) + CHUNK_PAUSE_SECONDS
+                combine_mp3(temp_mp3s, output_file, CHUNK_PAUSE_SECONDS)
+
+            if captions_file:
+                write_webvtt(boundaries, captions_file)
+        finally:
             # Clean up temporary files
             for mp3 in temp_mp3s:
                 if mp3.exists():
                     mp3.unlink()
 
-    except Exception as e:
-        logger.error(f"TTS generation failed: {e}")
-        raise
+        return output_file
+
+    def list_voices(self) -> List[str]:
+        return get_available_voices()
+
+    def capabilities(self) -> Dict[str, Any]:
+        caps = super().capabilities()
+        caps.update({"concurrent": True, "captions": True, "languages": ["multilingual"]})
+        return caps
+
+
+class GeminiTTSBackend(TTSBackend):
+    """Google Gemini TTS, using the key rotation in ``core/gemini_tts.py``."""
+
+    name = "gemini"
+    default_voice = "Algieba"
+
+    async def synthesize(self, text: str, output_file: Path, voice: Optional[str] = None,
+                         temp_dir: Optional[Path] = None) -> Path:
+        from core import gemini_tts
+
+        voice = voice or self.default_voice
+        if temp_dir is None:
+            temp_dir = output_file.parent / "tmp"
+        temp_dir.mkdir(parents=True, exist_ok=True)
+
+        wav_path = temp_dir / f"gemini_{voice}.wav"
+        try:
+            await asyncio.to_thread(gemini_tts.synthesize_to_file, text, voice, str(wav_path))
+            if output_file.suffix.lower() == ".wav":
+                wav_path.replace(output_file)
+            else:
+                convert_audio(wav_path, output_file)
+        finally:
+            if wav_path.exists():
+                wav_path.unlink()
+
+        return output_file
+
+    async def synthesize_dialogue(self, script: str, output_file: Path, speaker_voices: Optional[Dict[str, str]] = None,
+                                  temp_dir: Optional[Path] = None,
+                                  speakers: Optional[List[str]] = None) -> Dict[str, str]:
+        """
+        Render a labelled two-speaker script (``Host: ...`` / ``Guest: ...``) as one file.
+
+        Args:
+            script: Dialogue script with one ``Speaker: text`` turn per line
+            output_file: Output audio file path
+            speaker_voices: Optional explicit {speaker: voice} mapping
+            temp_dir: Directory for intermediate files
+            speakers: Speaker labels used in the script; other "Label:" lines
+                are read as part of the previous turn
+
+        Returns:
+            The {speaker: voice} mapping that was used
+        """
+        from core import gemini_tts
+
+        if temp_dir is None:
+            temp_dir = output_file.parent / "tmp"
+        temp_dir.mkdir(parents=True, exist_ok=True)
+
+        wav_path = temp_dir / "gemini_dialogue.wav"
+        try:
+            _, used_voices = await asyncio.to_thread(
+                gemini_tts.synthesize_dialogue, script, str(wav_path), speaker_voices, speakers
+            )
+            if output_file.suffix.lower() == ".wav":
+                wav_path.replace(output_file)
+            else:
+                convert_audio(wav_path, output_file)
+        finally:
+            if wav_path.exists():
+                wav_path.unlink()
+
+        if MASTERING_ENABLED:
+            await asyncio.to_thread(master_audio, output_file)
+        return used_voices
+
+    def list_voices(self) -> List[str]:
+        return [
+            "Zephyr", "Puck", "Charon", "Kore", "Fenrir", "Leda", "Orus", "Aoede",
+            "Callirrhoe", "Autonoe", "Enceladus", "Iapetus", "Umbriel", "Algieba",
+            "Despina", "Erinome", "Algenib", "Rasalgethi", "Laomedeia", "Achernar",
+            "Alnilam", "Schedar", "Gacrux", "Pulcherrima", "Achird", "Zubenelgenubi",
+            "Vindemiatrix", "Sadachbia", "Sadaltager", "Sulafat",
+        ]
+
+    def capabilities(self) -> Dict[str, Any]:
+        caps = super().capabilities()
+        caps.update({"max_chars": GEMINI_MAX_CHARS, "output_format": "wav", "languages": ["multilingual"],
+                     "dialogue": True})
+        return caps
+
+
+class MiniMaxTTSBackend(TTSBackend):
+    """MiniMax speech API, wrapping ``core/minimax_tts.py::MiniMaxTTS``."""
+
+    name = "minimax"
+    default_voice = "male-qn-qingse"
+
+    def __init__(self, model: str = "speech-01-turbo"):
+        self.model = model
+
+    async def synthesize(self, text: str, output_file: Path, voice: Optional[str] = None,
+                         temp_dir: Optional[Path] = None) -> Path:
+        from core.minimax_tts import MiniMaxTTS
+
+        api_key = os.getenv("MINIMAX_API_KEY")
+        group_id = os.getenv("MINIMAX_GROUP_ID")
+        if not api_key or not group_id:
+            raise RuntimeError("MINIMAX_API_KEY and MINIMAX_GROUP_ID must be set")
+
+        client = MiniMaxTTS(api_key, group_id)
+        result = await asyncio.to_thread(
+            client.text_to_speech,
+            text=text,
+            output_path=str(output_file),
+            model=self.model,
+            voice_id=voice or self.default_voice,
+        )
+
+        # text_to_speech reports success even when combining the chunks failed
+        if not output_file.exists() or output_file.stat().st_size == 0:
+            for chunk_file in (result or {}).get("chunk_files", []):
+                Path(chunk_file).unlink(missing_ok=True)
+            raise RuntimeError(f"MiniMax did not produce {output_file.name}")
+        return output_file
+
+    def list_voices(self) -> List[str]:
+        return [
+            "male-qn-qingse", "male-qn-jingying", "male-qn-badao", "male-qn-daxuesheng",
+            "female-shaonv", "female-yujie", "female-chengshu", "female-tianmei",
+            "presenter_male", "presenter_female", "audiobook_male_1", "audiobook_female_1",
+        ]
+
+    def capabilities(self) -> Dict[str, Any]:
+        caps = super().capabilities()
+        caps.update({"max_chars": 500, "languages": ["zh", "en"]})
+        return caps
+
+
+class LocalTTSBackend(TTSBackend):
+    """
+    Offline synthesis with a Piper model on disk, or espeak-ng as a fallback.
+
+    Piper is used when ``PIPER_MODEL`` points to an ``.onnx`` voice model (or
+    ``voice`` names a model in ``PIPER_MODEL_DIR``) and the ``piper`` binary is
+    installed. Otherwise ``espeak-ng``/``espeak`` is used with ``voice`` as the
+    espeak voice name (``cmn`` is picked automatically for Chinese text).
+    """
+
+    name = "local"
+    default_voice = "en-us"
+
+    def _piper_model(self, voice: Optional[str]) -> Optional[Path]:
+        """Resolve a Piper model from the voice name or environment."""
+        if voice and voice.endswith(".onnx") and Path(voice).exists():
+            return Path(voice)
+        model_dir = os.getenv("PIPER_MODEL_DIR")
+        if voice and model_dir and (Path(model_dir) / f"{voice}.onnx").exists():
+            return Path(model_dir) / f"{voice}.onnx"
+        model = os.getenv("PIPER_MODEL")
+        if model and Path(model).exists():
+            return Path(model)
+        return None
+
+    @staticmethod
+    def _espeak_binary() -> Optional[str]:
+        return shutil.which("espeak-ng") or shutil.which("espeak")
+
+    async def synthesize(self, text: str, output_file: Path, voice: Optional[str] = None,
+                         temp_dir: Optional[Path] = None) -> Path:
+        if temp_dir is None:
+            temp_dir = output_file.parent / "tmp"
+        temp_dir.mkdir(parents=True, exist_ok=True)
+
+        text_path = temp_dir / "local_tts_input.txt"
+        wav_path = temp_dir / "local_tts_output.wav"
+        text_path.write_text(text, encoding="utf-8")
+
+        model = self._piper_model(voice)
+        try:
+            if model and shutil.which("piper"):
+                logger.info(f"Local TTS: Piper model {model.name}")
+                with open(text_path, "r", encoding="utf-8") as stdin:
+                    await asyncio.to_thread(
+                        subprocess.run,
+                        ["piper", "--model", str(model), "--output_file", str(wav_path)],
+                        stdin=stdin, check=True, capture_output=True,
+                    )
+            else:
+                espeak = self._espeak_binary()
+                if not espeak:
+                    raise RuntimeError("No offline TTS available: install espeak-ng or set PIPER_MODEL")
+                if voice is None or voice.endswith(".onnx"):
+                    voice = "cmn" if CJK_PATTERN.search(text) else self.default_voice
+                logger.info(f"Local TTS: {Path(espeak).name} voice {voice}")
+                await asyncio.to_thread(
+                    subprocess.run,
+                    [espeak, "-v", voice, "-s", "165", "-f", str(text_path), "-w", str(wav_path)],
+                    check=True, capture_output=True,
+                )
+
+            if output_file.suffix.lower() == ".wav":
+                wav_path.replace(output_file)
+            else:
+                convert_audio(wav_path, output_file)
+        except subprocess.CalledProcessError as e:
+            stderr = e.stderr.decode(errors="replace") if isinstance(e.stderr, bytes) else e.stderr
+            logger.error(f"Local TTS failed: {stderr}")
+            raise
+        finally:
+            for temp_file in (text_path, wav_path):
+                if temp_file.exists():
+                    temp_file.unlink()
+
+        return output_file
+
+    def list_voices(self) -> List[str]:
+        voices = []
+        model_dir = os.getenv("PIPER_MODEL_DIR")
+        if model_dir and Path(model_dir).is_dir():
+            voices.extend(sorted(model.stem for model in Path(model_dir).glob("*.onnx")))
+
+        espeak = self._espeak_binary()
+        if espeak:
+            result = subprocess.run([espeak, "--voices"], capture_output=True, text=True)
+            # Columns: Pty Language Age/Gender VoiceName File Other Languages
+            for line in result.stdout.splitlines()[1:]:
+                columns = line.split()
+                if len(columns) >= 2:
+                    voices.append(columns[1])
+        return voices
+
+    def capabilities(self) -> Dict[str, Any]:
+        caps = super().capabilities()
+        caps.update({
+            "max_chars": None,
+            "requires_network": False,
+            "output_format": "wav",
+            "languages": ["multilingual"],
+        })
+        return caps
+
+
+TTS_BACKENDS = {
+    "edge": EdgeTTSBackend,
+    "gemini": GeminiTTSBackend,
+    "minimax": MiniMaxTTSBackend,
+    "local": LocalTTSBackend,
+}
+
+# Backends tried, in order, when the requested one fails ("local" only with tts.local_fallback)
+TTS_FALLBACKS = {
+    "edge": ["local"],
+    "gemini": ["edge", "local"],
+    "minimax": ["edge", "local"],
+}
+
+DEFAULT_TTS_BACKEND = os.getenv("TTS_BACKEND", "edge")
+
+
+def get_tts_backend(name: str) -> TTSBackend:
+    """
+    Look up a TTS backend by name.
+
+    Args:
+        name: Backend name (see ``TTS_BACKENDS``)
+
+    Returns:
+        Backend instance
+    """
+    try:
+        return TTS_BACKENDS[name.lower()]()
+    except KeyError:
+        raise ValueError(f"Unknown TTS backend '{name}'. Available: {', '.join(TTS_BACKENDS)}")
+
+
+async def generate_audio_from_text(text: str, output_file: Path, voice: Optional[str] = None, temp_dir: Path = None,
+                                   backend: str = DEFAULT_TTS_BACKEND, fallback: bool = True,
+                                   master: bool = MASTERING_ENABLED, captions: bool = False) -> str:
+    """
+    Generate audio from text with the selected TTS backend.
+
+    If the backend fails (e.g. Gemini on all keys) and ``fallback`` is set, the
+    backends listed in ``TTS_FALLBACKS`` are tried in order with their own
+    default voices; the offline ``local`` backend only if ``tts.local_fallback``
+    is enabled. Callers can compare the returned backend with the requested
+    one to tell that a fallback was used. The result is then mastered (see ``master_audio``) so
+    every provider ends up at the same loudness.
+
+    With ``captions``, backends that report word timings also write a WebVTT
+    file next to the audio (``narration.mp3`` -> ``narration.vtt``). Silence
+    trimming is skipped in that case so the timings stay aligned.
+
+    Results are cached by text, voice, backend, chunking and mastering
+    settings, so an unchanged narration is restored instead of synthesized
+    again. Fallback takes are not cached, so the next run tries the
+    requested backend again.
+
+    Args:
+        text: Text to convert to speech
+        output_file: Output audio file path
+        voice: Voice for the requested backend (backend default if None)
+        temp_dir: Temporary directory for chunk processing
+        backend: Name of the TTS backend to use
+        fallback: Whether to try fallback backends on failure
+        master: Whether to apply the mastering chain to the result
+        captions: Whether to write word-timed WebVTT captions when supported
+
+    Returns:
+        Name of the backend that produced the audio
+    """
+    fallbacks = [name for name in TTS_FALLBACKS.get(backend, []) if name != "local" or settings.tts_local_fallback]
+    chain = [backend] + (fallbacks if fallback else [])
+    captions_file = caption_path_for(output_file)
+    if captions and captions_file.exists():
+        captions_file.unlink()  # Never leave captions from a previous take
+
+    key = cache_key(text, voice, chain, master, MASTERING_TARGET_LUFS, captions, CHUNK_PAUSE_SECONDS,
+                    GEMINI_MAX_CHARS)
+    cached = step_cache.get("tts", key)
+    if cached and step_cache.get_files("tts", key, {
+        "audio.mp3": output_file,
+        "captions.vtt": captions_file if cached.get("captions") else None,
+    }):
+        return cached["backend"]
+
+    last_error = None
+    for index, name in enumerate(chain):
+        tts = get_tts_backend(name)
+        with_captions = captions and tts.capabilities().get("captions", False)
+        try:
+            logger.info(f"Generating audio with '{name}' backend")
+            if with_captions:
+                await tts.synthesize(text, output_file, voice if index == 0 else None, temp_dir,
+                                     captions_file=captions_file)
+            else:
+                await tts.synthesize(text, output_file, voice if index == 0 else None, temp_dir)
+        except Exception as e:
+            last_error = e
+            if index < len(chain) - 1:
+                logger.warning(f"TTS backend '{name}' failed: {e}. Falling back to '{chain[index + 1]}'")
+            else:
+                logger.error(f"TTS generation failed: {e}")
+            continue
+
+        if index > 0:
+            logger.warning(f"⚠️ Narration was produced by the fallback backend '{name}' instead of '{backend}'")
+
+        if master:
+            await asyncio.to_thread(master_audio, output_file, trim_silence=not with_captions)
+
+        if index == 0:
+            has_captions = with_captions and captions_file.exists()
+            step_cache.put_files("tts", key, {
+                "audio.mp3": output_file,
+                "captions.vtt": captions_file if has_captions else None,
+            })
+            step_cache.put("tts", key, {"backend": name, "captions": has_captions})
+        return name
+
+    raise last_error
+
+
+def caption_path_for(audio_file: Path) -> Path:
+    """WebVTT captions file that belongs to an audio file."""
+    return audio_file.with_suffix(".vtt")
 
 
 def get_available_voices() -> List[str]:
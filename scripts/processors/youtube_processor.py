@@ -3,6 +3,7 @@ YouTube Processor Module
 Handles YouTube video processing, metadata extraction, and audio downloading.
 """
 
+import html
 import logging
 import re
 from pathlib import Path
@@ -12,6 +13,9 @@ from urllib.parse import urlparse, parse_qs, urlunparse, urlencode
 import requests
 from yt_dlp import YoutubeDL
 
+from .cache import cache_key, step_cache
+from .config import settings
+
 logger = logging.getLogger(__name__)
 
 
@@ -44,6 +48,95 @@ def clean_youtube_url(url: str) -> str:
     return cleaned_url
 
 
+YOUTUBE_HOSTS = ("www.youtube.com", "youtube.com", "m.youtube.com")
+# Channel pages (@handle, /channel/ID, /c/NAME, /user/NAME), optionally a listing tab
+CHANNEL_PATH = re.compile(r"^/(?:@[^/]+|channel/[^/]+|c/[^/]+|user/[^/]+)(?:/(videos|streams|shorts))?/?$")
+
+
+def is_youtube_collection_url(url: str) -> bool:
+    """
+    Check whether a URL is a YouTube playlist or channel rather than one video.
+
+    Watch URLs with a ``list=`` parameter count as single videos.
+
+    Args:
+        url: URL to check
+
+    Returns:
+        True for ``/playlist?list=...`` and channel URLs
+    """
+    parsed = urlparse(url)
+    if parsed.hostname not in YOUTUBE_HOSTS:
+        return False
+    if parsed.path.rstrip("/") == "/playlist":
+        return "list" in parse_qs(parsed.query)
+    return bool(CHANNEL_PATH.match(parsed.path))
+
+
+def fetch_youtube_collection(url: str) -> Optional[Dict[str, Any]]:
+    """
+    List the videos of a playlist or channel with yt-dlp's flat extraction.
+
+    Flat extraction reads only the listing, not each video's page, so
+    ``upload_date`` and ``duration`` of entries may be missing.
+
+    Args:
+        url: Playlist or channel URL
+
+    Returns:
+        Dictionary with ``id``, ``kind`` ("playlist" or "channel"), ``title``,
+        ``description``, ``uploader``, ``webpage_url`` and ``entries`` (each
+        with ``id``, ``title``, ``url``, ``upload_date``, ``duration``), or
+        None if failed
+    """
+    parsed = urlparse(url)
+    kind = "playlist" if parsed.path.rstrip("/") == "/playlist" else "channel"
+    list_url = url
+    channel = CHANNEL_PATH.match(parsed.path)
+    if channel and not channel.group(1):
+        # A channel's home page lists its tabs, not videos; uploads are on /videos
+        list_url = urlunparse(parsed._replace(path=parsed.path.rstrip("/") + "/videos", query=""))
+    logger.info(f"Listing YouTube {kind}: {list_url}")
+
+    ydl_opts = {
+        "quiet": True,
+        "no_warnings": True,
+        "skip_download": True,
+        "extract_flat": "in_playlist",
+    }
+
+    try:
+        with YoutubeDL(ydl_opts) as ydl:  # type: ignore
+            info = ydl.extract_info(list_url, download=False)
+    except Exception as e:
+        logger.error(f"Failed to list YouTube {kind}: {e}")
+        return None
+
+    entries = []
+    for entry in info.get("entries") or []:
+        # Skip removed videos and nested playlists (e.g. channel tabs)
+        if not entry or not entry.get("id") or entry.get("_type") == "playlist":
+            continue
+        entries.append({
+            "id": entry["id"],
+            "title": entry.get("title"),
+            "url": f"https://www.youtube.com/watch?v={entry['id']}",
+            "upload_date": entry.get("upload_date"),
+            "duration": entry.get("duration"),
+        })
+
+    title = info.get("title") or info.get("channel") or info.get("uploader") or "YouTube"
+    return {
+        "id": info.get("id"),
+        "kind": kind,
+        "title": re.sub(r" - (?:Videos|Streams|Shorts|Live)$", "", title),
+        "description": info.get("description") or "",
+        "uploader": info.get("uploader") or info.get("channel"),
+        "webpage_url": info.get("webpage_url") or url,
+        "entries": entries,
+    }
+
+
 def fetch_youtube_info(url: str) -> Optional[Dict[str, Any]]:
     """
     Fetch YouTube video metadata using yt-dlp.
@@ -61,25 +154,15 @@ def fetch_youtube_info(url: str) -> Optional[Dict[str, Any]]:
         "quiet": True,
         "skip_download": True,
         "no_warnings": True,
-        "writesubtitles": True,  # Enable subtitle extraction
-        "writeautomaticsub": True,  # Include auto-generated subtitles
-        "subtitleslangs": ['en'],  # Prefer English subtitles
     }
 
     try:
         with YoutubeDL(ydl_opts) as ydl:  # type: ignore
             info = ydl.extract_info(clean_url, download=False)
 
-        # Extract subtitle information
-        subtitles = info.get("subtitles", {})
-        automatic_captions = info.get("automatic_captions", {})
-
-        # Check for available subtitles
-        available_subs = []
-        if 'en' in subtitles:
-            available_subs.extend(subtitles['en'])
-        if 'en' in automatic_captions:
-            available_subs.extend(automatic_captions['en'])
+        # Subtitle tracks of every language (see subtitle_tracks)
+        subtitles = info.get("subtitles") or {}
+        automatic_captions = info.get("automatic_captions") or {}
 
         return {
             "title": info.get("title", "Untitled Video"),
@@ -92,54 +175,160 @@ def fetch_youtube_info(url: str) -> Optional[Dict[str, Any]]:
             "webpage_url": info.get("webpage_url"),
             "tags": info.get("tags", []),
             "view_count": info.get("view_count", 0),
+            "chapters": info.get("chapters") or [],
             "subtitles": subtitles,
             "automatic_captions": automatic_captions,
-            "has_subtitles": bool(available_subs),
+            "language": info.get("language"),
+            "has_subtitles": bool(subtitle_tracks(info)),
         }
     except Exception as e:
         logger.error(f"Failed to fetch YouTube info: {e}")
         return None
 
 
-def download_youtube_subtitles(video_id: str, output_dir: Path) -> Optional[str]:
+# Live chat replays are listed as a subtitle track but are not captions
+NON_CAPTION_TRACKS = {"live_chat"}
+# Suffix yt-dlp gives the automatic captions in the spoken language (the others are machine translations)
+ORIGINAL_SUFFIX = "-orig"
+
+
+def _strip_original(key: str) -> str:
+    return key[:-len(ORIGINAL_SUFFIX)] if key.endswith(ORIGINAL_SUFFIX) else key
+
+
+def _base_language(code: str) -> str:
+    return code.lower().split("-")[0]
+
+
+def _matching_track(tracks: Dict[str, Any], language: str) -> Optional[str]:
+    """
+    Key of the subtitle track for a language: an exact match first, then the
+    same base language (``zh`` finds ``zh-Hans``), original tracks before
+    translations.
+    """
+    keys = sorted((key for key in tracks if key not in NON_CAPTION_TRACKS),
+                  key=lambda key: not key.endswith(ORIGINAL_SUFFIX))
+    for key in keys:
+        if _strip_original(key).lower() == language.lower():
+            return key
+    for key in keys:
+        if _base_language(key) == _base_language(language):
+            return key
+    return None
+
+
+def original_language(info: Dict[str, Any]) -> Optional[str]:
+    """
+    The language spoken in a video.
+
+    yt-dlp's ``language`` field when YouTube reports one, otherwise the
+    language of the original automatic captions track.
+    """
+    if info.get("language"):
+        return info["language"]
+    for key in info.get("automatic_captions") or {}:
+        if key.endswith(ORIGINAL_SUFFIX):
+            return _strip_original(key)
+    return None
+
+
+def subtitle_tracks(info: Dict[str, Any], languages: Optional[List[str]] = None) -> List[Dict[str, Any]]:
+    """
+    Subtitle tracks to build the transcript from, best first.
+
+    The video's own language comes first, with manual subtitles preferred
+    over automatic captions; then each of ``languages`` in order. Callers
+    try each track until one downloads.
+
+    Args:
+        info: Video info with ``subtitles``, ``automatic_captions`` and ``language``
+        languages: Fallback languages (``subtitles.languages`` if None)
+
+    Returns:
+        List of ``{"track", "language", "automatic"}`` (``track`` is the
+        yt-dlp key), empty if no track matches
+    """
+    manual = info.get("subtitles") or {}
+    automatic = info.get("automatic_captions") or {}
+
+    wanted = []
+    original = original_language(info)
+    if original:
+        wanted.append(original)
+    wanted += [lang for lang in (languages or settings.subtitle_languages) if lang not in wanted]
+
+    candidates = []
+    for language in wanted:
+        for tracks, is_automatic in ((manual, False), (automatic, True)):
+            key = _matching_track(tracks, language)
+            if key and not any(c["track"] == key and c["automatic"] == is_automatic for c in candidates):
+                candidates.append({
+                    "track": key,
+                    "language": _strip_original(key),
+                    "automatic": is_automatic,
+                })
+    return candidates
+
+
+def translation_track(info: Dict[str, Any], target: str, source_language: str) -> Optional[Dict[str, Any]]:
+    """
+    YouTube's machine translation of the captions into ``target``.
+
+    Returns:
+        Track in the ``subtitle_tracks`` format, or None if the
+        transcript is already in that language or YouTube offers no translation
+    """
+    if _base_language(source_language) == _base_language(target):
+        return None
+    automatic = {key: value for key, value in (info.get("automatic_captions") or {}).items()
+                 if not key.endswith(ORIGINAL_SUFFIX)}
+    key = _matching_track(automatic, target)
+    return {"track": key, "language": key, "automatic": True} if key else None
+
+
+def download_youtube_subtitles(video_id: str, output_dir: Path,
+                               track: Dict[str, Any]) -> Optional[List[Dict[str, Any]]]:
     """
     Download YouTube subtitles/captions for a video.
 
     Args:
         video_id: YouTube video ID
         output_dir: Directory to save subtitle files
+        track: Track from ``subtitle_tracks`` or ``translation_track``
 
     Returns:
-        Path to the subtitle file if successful, None otherwise
+        Timed transcript segments (see ``parse_vtt_segments``), or None if
+        the video has no usable subtitles
     """
     logger.info(f"Attempting to download subtitles for video: {video_id}")
+    url = f"https://www.youtube.com/watch?v={video_id}"
 
     ydl_opts = {
         'skip_download': True,
-        'writesubtitles': True,
-        'writeautomaticsub': True,
-        'subtitleslangs': ['en'],
+        'writesubtitles': not track["automatic"],
+        'writeautomaticsub': track["automatic"],
+        'subtitleslangs': [track["track"]],
+        'subtitlesformat': 'vtt/srt',
         'outtmpl': str(output_dir / 'subtitles'),
         'quiet': True,
         'no_warnings': True,
     }
 
     try:
-        url = f"https://www.youtube.com/watch?v={video_id}"
         with YoutubeDL(ydl_opts) as ydl:  # type: ignore
-            info = ydl.extract_info(url, download=True)
+            ydl.extract_info(url, download=True)
 
         # Check for downloaded subtitle files
-        subtitle_files = list(output_dir.glob("subtitles*.vtt")) + list(output_dir.glob("subtitles*.srt"))
+        pattern = f"subtitles.{track['track']}"
+        subtitle_files = list(output_dir.glob(f"{pattern}.vtt")) + list(output_dir.glob(f"{pattern}.srt"))
 
         if subtitle_files:
             subtitle_file = subtitle_files[0]
             logger.info(f"✅ Downloaded subtitles: {subtitle_file}")
 
-            # Convert to text content
-            transcript = extract_text_from_vtt(subtitle_file)
-            if transcript:
-                return transcript
+            segments = parse_vtt_segments(subtitle_file)
+            if len(segments_to_text(segments)) > 100:  # Minimum length check
+                return segments
             else:
                 logger.warning("⚠️ Could not extract text from subtitle file")
                 return None
@@ -152,6 +341,97 @@ def download_youtube_subtitles(video_id: str, output_dir: Path) -> Optional[str]
         return None
 
 
+VTT_TIMING = re.compile(r"(?:(\d+):)?(\d{2}):(\d{2})[.,](\d{3})\s*-->\s*(?:(\d+):)?(\d{2}):(\d{2})[.,](\d{3})")
+
+
+def _vtt_seconds(hours: Optional[str], minutes: str, seconds: str, millis: str) -> float:
+    return int(hours or 0) * 3600 + int(minutes) * 60 + int(seconds) + int(millis) / 1000
+
+
+def parse_vtt_segments(vtt_file: Path) -> List[Dict[str, Any]]:
+    """
+    Parse a VTT/SRT subtitle file into timed transcript segments.
+
+    YouTube's automatic captions roll: each cue repeats the previous cue's
+    last line and adds a new one, so lines of the previous cue are skipped.
+
+    Args:
+        vtt_file: Path to the subtitle file
+
+    Returns:
+        List of ``{"start": seconds, "end": seconds, "text": str}`` in order
+        (empty if the file cannot be read)
+    """
+    try:
+        content = vtt_file.read_text(encoding="utf-8")
+    except OSError as e:
+        logger.error(f"Failed to read subtitle file: {e}")
+        return []
+
+    # Cue text may contain whitespace-only lines (YouTube's do), so cues are
+    # split at timing lines rather than at blank lines
+    lines = content.replace("\r\n", "\n").split("\n")
+    cues = []
+    for i, line in enumerate(lines):
+        match = VTT_TIMING.search(line) if "-->" in line else None
+        if match:
+            if cues and cues[-1]["lines"] and lines[i - 1].strip():
+                cues[-1]["lines"].pop()  # Identifier of this cue (e.g. SRT numbering)
+            cues.append({"start": _vtt_seconds(*match.groups()[:4]), "end": _vtt_seconds(*match.groups()[4:]),
+                         "lines": []})
+        elif cues:
+            cues[-1]["lines"].append(line)
+
+    segments = []
+    previous_lines: List[str] = []
+    for cue in cues:
+        # Drop inline word timings (<00:00:08.800>), styling tags (<c>) and entities
+        cue_lines = [html.unescape(re.sub(r"<[^>]+>", "", line)).strip() for line in cue["lines"]]
+        cue_lines = [line for line in cue_lines if line]
+        for text in cue_lines:
+            if text not in previous_lines:
+                segments.append({"start": round(cue["start"], 3), "end": round(cue["end"], 3), "text": text})
+        previous_lines = cue_lines
+    return segments
+
+
+def segments_to_text(segments: List[Dict[str, Any]]) -> str:
+    """Join transcript segments into plain text."""
+    return re.sub(r"\s+", " ", " ".join(segment["text"] for segment in segments)).strip()
+
+
+def group_segments(segments: List[Dict[str, Any]], min_seconds: float = 20,
+                   max_seconds: float = 60) -> List[Dict[str, Any]]:
+    """
+    Merge transcript segments into paragraphs.
+
+    A paragraph ends at the first sentence end after ``min_seconds``, or
+    after ``max_seconds`` regardless (automatic captions have no punctuation).
+
+    Args:
+        segments: Segments from ``parse_vtt_segments``
+        min_seconds: Minimum paragraph length before breaking at a sentence end
+        max_seconds: Maximum paragraph length
+
+    Returns:
+        List of ``{"start": seconds, "text": str}``
+    """
+    paragraphs = []
+    current: List[str] = []
+    start = 0.0
+    for segment in segments:
+        if not current:
+            start = segment["start"]
+        current.append(segment["text"])
+        elapsed = segment["end"] - start
+        if elapsed >= max_seconds or (elapsed >= min_seconds and re.search(r"[.!?。！？]$", segment["text"])):
+            paragraphs.append({"start": start, "text": " ".join(current)})
+            current = []
+    if current:
+        paragraphs.append({"start": start, "text": " ".join(current)})
+    return paragraphs
+
+
 def extract_text_from_vtt(vtt_file: Path) -> Optional[str]:
     """
     Extract plain text from a VTT subtitle file.
@@ -162,75 +442,8 @@ def extract_text_from_vtt(vtt_file: Path) -> Optional[str]:
     Returns:
         Extracted text content or None if failed
     """
-    try:
-        with open(vtt_file, 'r', encoding='utf-8') as f:
-            content = f.read()
-
-        # Split into lines
-        lines = content.split('\n')
-        text_lines = []
-        current_text = []
-
-        for line in lines:
-            line = line.strip()
-
-            # Skip empty lines, WEBVTT header, timing lines, and metadata
-            if (not line or
-                line.startswith('WEBVTT') or
-                '-->' in line or
-                line.startswith('Kind:') or
-                line.startswith('Language:') or
-                line.isdigit()):
-                # If we have accumulated text, add it to our collection
-                if current_text:
-                    combined_text = ' '.join(current_text)
-                    if combined_text.strip():
-                        text_lines.append(combined_text.strip())
-                    current_text = []
-                continue
-
-            # Clean up HTML tags and timing information
-            # Remove HTML tags like <c>, </c>, and timing info like <00:00:08.800>
-            clean_line = re.sub(r'<[^>]+>', '', line)
-            # Remove timing patterns like <00:00:08.800>
-            clean_line = re.sub(r'<\d{2}:\d{2}:\d{2}\.\d{3}>', '', clean_line)
-            clean_line = clean_line.strip()
-
-            if clean_line:
-                current_text.append(clean_line)
-
-        # Add any remaining text
-        if current_text:
-            combined_text = ' '.join(current_text)
-            if combined_text.strip():
-                text_lines.append(combined_text.strip())
-
-        # Join all text lines and clean up
-        transcript = ' '.join(text_lines)
-
-        # Clean up extra whitespace and normalize
-        transcript = re.sub(r'\s+', ' ', transcript).strip()
-
-        # Remove duplicate phrases that appear due to overlapping captions
-        # This is a simple approach - split into sentences and deduplicate
-        if len(transcript) > 100:
-            # Split on common sentence endings and deduplicate
-            sentences = re.split(r'(?<=[.!?])\s+', transcript)
-            seen = set()
-            unique_sentences = []
-            for sentence in sentences:
-                sentence = sentence.strip()
-                if sentence and sentence not in seen:
-                    seen.add(sentence)
-                    unique_sentences.append(sentence)
-
-            transcript = ' '.join(unique_sentences)
-
-        return transcript if len(transcript.strip()) > 100 else None  # Minimum length check
-
-    except Exception as e:
-        logger.error(f"Failed to extract text from VTT file: {e}")
-        return None
+    transcript = segments_to_text(parse_vtt_segments(vtt_file))
+    return transcript if len(transcript) > 100 else None  # Minimum length check
 
 
 def download_audio(video_id: str, filepath: Path) -> bool:
@@ -244,19 +457,20 @@ def download_audio(video_id: str, filepath: Path) -> bool:
     Returns:
         True if download successful, False otherwise
     """
-    logger.info(f"Downloading audio to {filepath}")
-    minimal_url = f"https://www.youtube.com/watch?v={video_id}"
-
-    # Method 1: yt-dlp with default settings
-    if _download_audio_yt_dlp(minimal_url, filepath, "default"):
+    key = cache_key(video_id)
+    if step_cache.get_files("download", key, {"audio.mp3": filepath}):
+        logger.info(f"♻️ Using cached audio for {video_id}")
         return True
 
-    # Method 2: yt-dlp with alternative settings
-    if _download_audio_yt_dlp(minimal_url, filepath, "alternative"):
-        return True
+    logger.info(f"Downloading audio to {filepath}")
+    minimal_url = f"https://www.youtube.com/watch?v={video_id}"
 
-    # Method 3: pytube fallback
-    if _download_audio_pytube(minimal_url, filepath):
+    # Method 1: yt-dlp with default settings, Method 2: yt-dlp with
+    # alternative settings, Method 3: pytube fallback
+    if (_download_audio_yt_dlp(minimal_url, filepath, "default")
+            or _download_audio_yt_dlp(minimal_url, filepath, "alternative")
+            or _download_audio_pytube(minimal_url, filepath)):
+        step_cache.put_files("download", key, {"audio.mp3": filepath})
         return True
 
     logger.error("❌ All audio download methods failed")
@@ -413,7 +627,7 @@ def split_audio_file(audio_filepath: Path, chunk_duration_minutes: int = 15) ->
 
             for i in range(num_chunks):
                 start_time = i * chunk_duration_seconds
-                chunk_path = chunk_dir / "06d"
+                chunk_path = chunk_dir / f"{i:06d}.mp3"
 
                 split_cmd = [
                     "ffmpeg", "-i", str(audio_filepath),
@@ -472,6 +686,23 @@ def format_duration(seconds: int) -> str:
     return f"{minutes}m"
 
 
+def format_timestamp(seconds: float) -> str:
+    """
+    Format a video position as ``m:ss`` (or ``h:mm:ss``).
+
+    Args:
+        seconds: Position in seconds
+
+    Returns:
+        Timestamp as shown on YouTube
+    """
+    total = int(seconds)
+    hours, minutes, secs = total // 3600, (total % 3600) // 60, total % 60
+    if hours:
+        return f"{hours}:{minutes:02d}:{secs:02d}"
+    return f"{minutes}:{secs:02d}"
+
+
 def sanitize_text(text: str) -> str:
     """
     Sanitize text for markdown frontmatter.
@@ -496,6 +727,10 @@ def get_youtube_video_id(url: str) -> Optional[str]:
         Video ID or None if not found
     """
     try:
+        # Channel handles would otherwise pass for 11-character video IDs below
+        if is_youtube_collection_url(url):
+            return None
+
         parsed_url = urlparse(url)
         hostname = parsed_url.hostname
 
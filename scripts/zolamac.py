@@ -0,0 +1,281 @@
+#!/usr/bin/env python3
+"""
+zolamac
+Single command line entry point over the pipelines in scripts/core.
+
+Examples:
+    python scripts/zolamac.py add web https://example.com/article
+    python scripts/zolamac.py add youtube https://www.youtube.com/watch?v=VIDEO_ID --podcast
+    python scripts/zolamac.py add youtube "https://www.youtube.com/playlist?list=LIST_ID" --after 2024-01-01 --max 5
+    python scripts/zolamac.py --provider local narrate my-post --force
+    python scripts/zolamac.py --dry-run rebuild
+"""
+
+import argparse
+import asyncio
+import logging
+import os
+import sys
+from pathlib import Path
+
+# Import our modular processors
+sys.path.append(str(Path(__file__).parent))
+
+from processors.config import CONFIG_FILE, settings, bundle_dirs, load_settings
+from processors.cache import add_cache_arguments, configure_cache
+from processors.tts_engine import TTS_BACKENDS, get_tts_backend
+from processors.bundle_schema import load_bundle_metadata
+from processors.front_matter import read_front_matter
+from processors.youtube_processor import is_youtube_collection_url, validate_youtube_url
+from processors.content_scraper import slugify
+
+from core import batch_processor, narrate_bundles, podcast_feed, validate_bundles
+from core.web_to_blog import process_web_article
+from core.youtube_to_blog import add_collection_arguments, process_youtube_collection, process_youtube_video
+
+logger = logging.getLogger("zolamac")
+
+
+def apply_global_options(args: argparse.Namespace) -> None:
+    """Load the config file and apply global command line overrides."""
+    if args.config:
+        if not args.config.exists():
+            logger.error(f"❌ Config file not found: {args.config}")
+            sys.exit(1)
+        # Update the shared object in place; the pipelines hold a reference to it
+        settings.update(**vars(load_settings(args.config)))
+
+    settings.update(
+        content_root=args.content_root,
+        tts_backend=args.provider,
+        narrate_backend=args.provider,
+        voice=args.voice,
+        dry_run=args.dry_run or None,
+    )
+
+    level = logging.INFO + 10 * (args.quiet - args.verbose)
+    logging.getLogger().setLevel(max(logging.DEBUG, min(level, logging.ERROR)))
+    configure_cache(args)
+
+
+def run_pipeline(url: str, podcast: bool = False, force: bool = False) -> bool:
+    """Run the web or YouTube pipeline for a URL (or describe it with --dry-run)."""
+    is_collection = is_youtube_collection_url(url)
+    is_youtube = is_collection or validate_youtube_url(url)
+    if settings.dry_run:
+        kind = "youtube collection" if is_collection else "youtube" if is_youtube else "web"
+        target = "" if is_youtube else f" -> {settings.content_root / slugify(url)}"
+        logger.info(f"🔎 Would import {kind}: {url}{target} (tts: {settings.tts_backend})")
+        return True
+
+    settings.content_root.mkdir(parents=True, exist_ok=True)
+    settings.output_folder.mkdir(exist_ok=True)
+    if is_collection:
+        return asyncio.run(process_youtube_collection(url, podcast=podcast))
+    if is_youtube:
+        return asyncio.run(process_youtube_video(url, podcast=podcast))
+    return asyncio.run(process_web_article(url, force=force))
+
+
+def cmd_add(args: argparse.Namespace) -> int:
+    """Import a web article, a YouTube video or a batch of URLs."""
+    if args.source == "batch":
+        if settings.dry_run:
+            for job in batch_processor.load_jobs(args.target):
+                run_pipeline(job["url"], bool(job.get("podcast")))
+            return 0
+        jobs = batch_processor.load_jobs(args.target)
+        urls = list(dict.fromkeys(job["url"] for job in jobs))
+        state = batch_processor.load_state(args.state)
+        batch_processor.merge_jobs(state, jobs, args.retry_failed)
+        batch_processor.save_state(state, args.state)
+        asyncio.run(batch_processor.process_batch(state, args.state, urls))
+        return 1 if batch_processor.print_summary(state, urls) else 0
+
+    url = args.target
+    if not url.startswith(("http://", "https://")):
+        logger.error("❌ Invalid URL format. Must start with http:// or https://")
+        return 1
+    if args.source == "youtube" and is_youtube_collection_url(url):
+        if not settings.dry_run:
+            settings.content_root.mkdir(parents=True, exist_ok=True)
+            settings.output_folder.mkdir(exist_ok=True)
+        return 0 if asyncio.run(process_youtube_collection(
+            url, podcast=args.podcast, after=args.after, before=args.before, max_videos=args.max_videos
+        )) else 1
+    if args.source == "youtube" and not validate_youtube_url(url):
+        logger.error("❌ Invalid YouTube URL format")
+        return 1
+    return 0 if run_pipeline(url, getattr(args, "podcast", False), getattr(args, "force", False)) else 1
+
+
+def cmd_narrate(args: argparse.Namespace) -> int:
+    """Regenerate narration.mp3 for existing bundles."""
+    post_dirs = bundle_dirs(args.slugs, lambda d: (d / "asset.txt").exists())
+    if settings.dry_run:
+        for post_dir in post_dirs:
+            exists = (post_dir / "narration.mp3").exists()
+            action = "keep" if exists and not args.force else "narrate"
+            logger.info(f"🔎 Would {action}: {post_dir.name} ({settings.narrate_backend})")
+        return 0
+    failures = asyncio.run(narrate_bundles.narrate_bundles(
+        post_dirs, settings.narrate_backend, settings.voice, args.force
+    ))
+    return 1 if failures else 0
+
+
+def cmd_voices(args: argparse.Namespace) -> int:
+    """List the voices of a TTS backend."""
+    backend = get_tts_backend(args.backend or settings.tts_backend)
+    for voice in backend.list_voices():
+        print(voice)
+    return 0
+
+
+def cmd_thumbnail(args: argparse.Namespace) -> int:
+    """Regenerate asset.jpg for bundles from their asset.txt and title."""
+    from processors.image_processor import generate_blog_thumbnail
+
+    failures = 0
+    for post_dir in bundle_dirs(args.slugs, lambda d: (d / "asset.txt").exists()):
+        thumb_path = post_dir / "asset.jpg"
+        if thumb_path.exists() and not args.force:
+            logger.info(f"⏭️ {post_dir.name}: asset.jpg already exists")
+            continue
+        if settings.dry_run:
+            logger.info(f"🔎 Would generate thumbnail: {thumb_path}")
+            continue
+
+        text = (post_dir / "asset.txt").read_text(encoding="utf-8")
+        title = read_front_matter(post_dir / "index.md").get("title", post_dir.name)
+        thumb_path.unlink(missing_ok=True)
+        if not generate_blog_thumbnail(text, title, post_dir, post_dir.name,
+                                       os.getenv("GROQ_API_KEY"), os.getenv("UNSPLASH_ACCESS_KEY")):
+            failures += 1
+    return 1 if failures else 0
+
+
+def cmd_validate(args: argparse.Namespace) -> int:
+    """Validate bundle asset.json files, optionally migrating them."""
+    migrate = args.migrate and not settings.dry_run
+    failures = [d.name for d in bundle_dirs(args.slugs) if not validate_bundles.check_bundle(d, migrate)]
+    if failures:
+        logger.error(f"💥 {len(failures)} bundle(s) do not conform: {', '.join(failures)}")
+        return 1
+    return 0
+
+
+def cmd_feed(args: argparse.Namespace) -> int:
+    """Record podcast episode audio details in bundle front matter."""
+    if settings.dry_run:
+        for post_dir in bundle_dirs(args.slugs):
+            audio = podcast_feed.find_episode_audio(post_dir)
+            logger.info(f"🔎 {post_dir.name}: {audio.name if audio else 'no episode audio'}")
+        return 0
+    episodes = sum(podcast_feed.prepare_bundle(post_dir) for post_dir in bundle_dirs(args.slugs))
+    logger.info(f"🎙️ {episodes} episode(s) ready for the podcast feed")
+    return 0
+
+
+def cmd_rebuild(args: argparse.Namespace) -> int:
+    """Re-run the import pipeline for bundles from the source URL in asset.json."""
+    failures = 0
+    for post_dir in bundle_dirs(args.slugs, lambda d: (d / "asset.json").exists()):
+        metadata = load_bundle_metadata(post_dir)
+        source = (metadata or {}).get("source", {})
+        if source.get("type") not in ("web", "youtube") or not source.get("url"):
+            logger.info(f"⏭️ {post_dir.name}: no web or YouTube source to rebuild from")
+            continue
+        podcast = any(step.get("name") == "podcast" for step in metadata.get("pipeline", []))
+        logger.info(f"🔁 Rebuilding {post_dir.name} from {source['url']}")
+        if not run_pipeline(source["url"], podcast, args.force):
+            failures += 1
+
+    if not settings.dry_run:
+        cmd_feed(argparse.Namespace(slugs=args.slugs))
+    return 1 if failures else 0
+
+
+def build_parser() -> argparse.ArgumentParser:
+    """Create the argument parser with global options and subcommands."""
+    parser = argparse.ArgumentParser(prog="zolamac", description="Turn web articles and YouTube videos into "
+                                     "narrated Zola page bundles")
+    parser.add_argument("--config", type=Path,
+                        help=f"Settings file (default: ZOLAMAC_CONFIG env or {CONFIG_FILE})")
+    parser.add_argument("--content-root", type=Path,
+                        help=f"Directory holding the page bundles (current: {settings.content_root})")
+    parser.add_argument("--provider", choices=sorted(TTS_BACKENDS),
+                        help=f"TTS backend (current: {settings.tts_backend}, narrate: {settings.narrate_backend})")
+    parser.add_argument("--voice", help="Voice name for the selected TTS backend")
+    parser.add_argument("--dry-run", "-n", action="store_true", help="Show what would be done without writing")
+    parser.add_argument("--verbose", "-v", action="count", default=0, help="More output (repeatable)")
+    parser.add_argument("--quiet", "-q", action="count", default=0, help="Less output (repeatable)")
+    add_cache_arguments(parser)
+
+    commands = parser.add_subparsers(dest="command", required=True, metavar="COMMAND")
+
+    add = commands.add_parser("add", help="Import new content into a page bundle")
+    sources = add.add_subparsers(dest="source", required=True, metavar="SOURCE")
+    web = sources.add_parser("web", help="Web article URL")
+    web.add_argument("target", metavar="URL")
+    web.add_argument("--force", "-f", action="store_true",
+                     help="Regenerate an already imported article from scratch, overwriting index.md edits")
+    youtube = sources.add_parser("youtube", help="YouTube video, playlist or channel URL")
+    youtube.add_argument("target", metavar="URL")
+    youtube.add_argument("--podcast", action="store_true",
+                         help="Also render a two-host Gemini podcast episode (needs GEMINI_API_KEY)")
+    add_collection_arguments(youtube)
+    batch = sources.add_parser("batch", help="File of URLs (.txt, .csv or .json), resumable")
+    batch.add_argument("target", metavar="FILE", type=Path)
+    batch.add_argument("--state", type=Path, default=batch_processor.DEFAULT_STATE_FILE,
+                       help=f"JSON file with per-URL progress (default: {batch_processor.DEFAULT_STATE_FILE})")
+    batch.add_argument("--retry-failed", action="store_true", help="Run previously failed jobs again")
+    add.set_defaults(func=cmd_add)
+
+    narrate = commands.add_parser("narrate", help="Regenerate narration.mp3 from asset.txt")
+    narrate.add_argument("slugs", nargs="*", help="Bundle folder names (default: all with asset.txt)")
+    narrate.add_argument("--force", "-f", action="store_true", help="Overwrite existing narration.mp3 files")
+    narrate.set_defaults(func=cmd_narrate)
+
+    voices = commands.add_parser("voices", help="List voices of a TTS backend")
+    voices.add_argument("backend", nargs="?", choices=sorted(TTS_BACKENDS),
+                        help="Backend (default: --provider or configured backend)")
+    voices.set_defaults(func=cmd_voices)
+
+    thumbnail = commands.add_parser("thumbnail", help="Generate asset.jpg thumbnails")
+    thumbnail.add_argument("slugs", nargs="*", help="Bundle folder names (default: all with asset.txt)")
+    thumbnail.add_argument("--force", "-f", action="store_true", help="Replace existing thumbnails")
+    thumbnail.set_defaults(func=cmd_thumbnail)
+
+    validate = commands.add_parser("validate", help="Check asset.json files against the bundle schema")
+    validate.add_argument("slugs", nargs="*", help="Bundle folder names (default: all)")
+    validate.add_argument("--migrate", action="store_true", help="Rewrite non-conforming bundles")
+    validate.set_defaults(func=cmd_validate)
+
+    feed = commands.add_parser("feed", help="Prepare podcast feed details before 'zola build'")
+    feed.add_argument("slugs", nargs="*", help="Bundle folder names (default: all)")
+    feed.set_defaults(func=cmd_feed)
+
+    rebuild = commands.add_parser("rebuild", help="Re-import bundles from their source URL (web posts keep "
+                                  "hand edits outside the generated section)")
+    rebuild.add_argument("slugs", nargs="*", help="Bundle folder names (default: all with asset.json)")
+    rebuild.add_argument("--force", "-f", action="store_true",
+                         help="Regenerate web posts from scratch instead of updating changed content only")
+    rebuild.set_defaults(func=cmd_rebuild)
+
+    return parser
+
+
+def main():
+    """Main entry point for the zolamac CLI."""
+    args = build_parser().parse_args()
+    apply_global_options(args)
+    try:
+        sys.exit(args.func(args))
+    except KeyboardInterrupt:
+        logger.warning("⏸️ Interrupted.")
+        sys.exit(130)
+
+
+if __name__ == "__main__":
+    main()
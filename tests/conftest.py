@@ -0,0 +1,9 @@
+"""
+Shared pytest setup: makes the ``processors`` and ``core`` packages under
+scripts/ importable the same way the entry scripts do.
+"""
+
+import sys
+from pathlib import Path
+
+sys.path.insert(0, str(Path(__file__).resolve().parent.parent / "scripts"))
@@ -0,0 +1,32 @@
+"""Tests for chapter estimation in processors.audio_metadata."""
+
+from processors.audio_metadata import estimate_chapter_times, markdown_headings
+
+
+def test_chapters_follow_heading_positions():
+    text = "Intro\n" + "a" * 44 + "\nMiddle\n" + "b" * 42 + "\nEnd\n"
+    chapters = estimate_chapter_times(text, [("h2", "Intro"), ("h2", "Middle"), ("h2", "End")], 100)
+    assert [title for _, title in chapters] == ["Intro", "Middle", "End"]
+    assert chapters[0][0] == 0.0
+    assert 45 < chapters[1][0] < 55
+    assert chapters[1][0] < chapters[2][0] < 100
+
+
+def test_headings_missing_from_the_narration_are_skipped():
+    chapters = estimate_chapter_times("Kept heading and some text", [("h2", "Dropped"), ("h2", "Kept")], 60)
+    assert chapters == [(0.0, "Kept")]
+
+
+def test_close_headings_are_merged():
+    chapters = estimate_chapter_times("One Two " + "x" * 1000, [("h2", "One"), ("h2", "Two")], 10)
+    assert [title for _, title in chapters] == ["One"]
+
+
+def test_no_chapters_without_duration_or_headings():
+    assert estimate_chapter_times("text", [("h2", "text")], 0) == []
+    assert estimate_chapter_times("text", [], 60) == []
+
+
+def test_markdown_headings():
+    markdown = "# Title\nbody\n### Part one ###\ntext\n##### Too deep\n#nospace"
+    assert markdown_headings(markdown) == [("h1", "Title"), ("h3", "Part one")]
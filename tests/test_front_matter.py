@@ -0,0 +1,61 @@
+"""Tests for front matter and generated-section edits in processors.front_matter."""
+
+from processors.front_matter import (
+    GENERATED_BEGIN, GENERATED_END, read_front_matter, replace_generated_section, set_extra_values, wrap_generated
+)
+
+POST = """+++
+title = "A post"
+date = 2024-05-01
+
+[extra]
+author = "Someone"
++++
+
+Hand-written intro.
+
+{generated}
+Hand-written outro.
+"""
+
+
+def write_post(tmp_path, body="Old body"):
+    md_path = tmp_path / "index.md"
+    md_path.write_text(POST.format(generated=wrap_generated(body)), encoding="utf-8")
+    return md_path
+
+
+def test_replace_generated_section_keeps_hand_edits(tmp_path):
+    md_path = write_post(tmp_path)
+    assert replace_generated_section(md_path, "New body")
+    content = md_path.read_text(encoding="utf-8")
+    assert "New body" in content and "Old body" not in content
+    assert "Hand-written intro." in content and "Hand-written outro." in content
+    assert content.count(GENERATED_BEGIN) == 1 and content.count(GENERATED_END) == 1
+
+
+def test_replace_generated_section_without_markers(tmp_path):
+    md_path = tmp_path / "index.md"
+    md_path.write_text("+++\ntitle = \"x\"\n+++\n\nBody\n", encoding="utf-8")
+    assert not replace_generated_section(md_path, "New body")
+    assert md_path.read_text(encoding="utf-8").endswith("Body\n")
+
+
+def test_set_extra_values_updates_adds_and_removes(tmp_path):
+    md_path = write_post(tmp_path)
+    set_extra_values(md_path, {"author": "Someone else", "narration": "narration.mp3"})
+    extra = read_front_matter(md_path)["extra"]
+    assert extra == {"author": "Someone else", "narration": "narration.mp3"}
+
+    set_extra_values(md_path, {"narration": None})
+    assert "narration" not in read_front_matter(md_path)["extra"]
+    assert "Hand-written intro." in md_path.read_text(encoding="utf-8")
+
+
+def test_set_extra_values_creates_extra_table(tmp_path):
+    md_path = tmp_path / "index.md"
+    md_path.write_text("+++\ntitle = \"x\"\n+++\n\nBody\n", encoding="utf-8")
+    set_extra_values(md_path, {"podcast_audio": "podcast.mp3"})
+    front_matter = read_front_matter(md_path)
+    assert front_matter["title"] == "x"
+    assert front_matter["extra"] == {"podcast_audio": "podcast.mp3"}
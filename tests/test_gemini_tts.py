@@ -0,0 +1,60 @@
+"""Tests for dialogue parsing, splitting and voice assignment in core.gemini_tts."""
+
+import pytest
+
+from core.gemini_tts import assign_speaker_voices, parse_dialogue, split_dialogue
+
+
+def test_parse_dialogue_turns():
+    turns = parse_dialogue("Host: Welcome back.\nGuest: Thanks for having me.\nHost: Let's start.")
+    assert turns == [("Host", "Welcome back."), ("Guest", "Thanks for having me."), ("Host", "Let's start.")]
+
+
+def test_labelled_looking_lines_continue_the_turn():
+    turns = parse_dialogue("Host: Here is the plan.\nGuest: Sounds good.\nStep 1: install it.\nNote: it is free.")
+    assert turns == [("Host", "Here is the plan."),
+                     ("Guest", "Sounds good. Step 1: install it. Note: it is free.")]
+
+
+def test_declared_speakers_only():
+    turns = parse_dialogue("Update: the news.\nAlex: Hi.\nSam: Hello.", speakers=["Alex", "Sam"])
+    assert [speaker for speaker, _ in turns] == ["Alex", "Sam"]
+    assert turns[0][1] == "Update: the news. Hi."
+
+
+def test_unlabelled_preamble_goes_to_first_speaker():
+    turns = parse_dialogue("Today we talk about tests.\nHost: Hello.\nGuest: Hi.")
+    assert turns[0] == ("Host", "Today we talk about tests. Hello.")
+
+
+def test_split_dialogue_keeps_pieces_under_limit():
+    turns = [("Host", "This is a sentence. " * 40), ("Guest", "Short answer.")]
+    pieces = split_dialogue(turns, 200)
+    assert len(pieces) > 1
+    assert all(len(piece) <= 200 for piece in pieces)
+    for piece in pieces:
+        assert all(line.startswith(("Host: ", "Guest: ")) for line in piece.splitlines())
+    assert pieces[-1].endswith("Guest: Short answer.\n")
+
+
+def test_split_dialogue_groups_short_turns():
+    pieces = split_dialogue([("Host", "Hi."), ("Guest", "Hello.")], 100)
+    assert pieces == ["Host: Hi.\nGuest: Hello.\n"]
+
+
+def test_speakers_get_distinct_voices_from_a_single_voice_list():
+    turns = [("Host", "a"), ("Guest", "b")]
+    speaker_voices = assign_speaker_voices(turns, ["Algieba"])
+    assert speaker_voices["Host"] == "Algieba"
+    assert speaker_voices["Guest"] != "Algieba"
+
+
+def test_voice_overrides_are_not_reused():
+    speaker_voices = assign_speaker_voices([("Host", "a"), ("Guest", "b")], ["Puck"], {"Guest": "Puck"})
+    assert speaker_voices["Guest"] == "Puck"
+    assert speaker_voices["Host"] != "Puck"
+
+
+def test_duplicate_override_voices_are_rejected():
+    with pytest.raises(ValueError):
+        assign_speaker_voices([("Host", "a"), ("Guest", "b")], ["Puck"], {"Host": "Kore", "Guest": "Kore"})
@@ -0,0 +1,36 @@
+"""Tests for Retry-After parsing and key selection in processors.key_pool."""
+
+import time
+from email.utils import formatdate
+
+from processors.key_pool import APIKeyPool, parse_retry_after
+
+
+def test_parse_retry_after_seconds_and_dates():
+    assert parse_retry_after("120") == 120.0
+    assert parse_retry_after("1.5") == 1.5
+    assert 50 < parse_retry_after(formatdate(time.time() + 60, usegmt=True)) <= 60
+    assert parse_retry_after(formatdate(time.time() - 60, usegmt=True)) == 0.0
+
+
+def test_parse_retry_after_invalid():
+    assert parse_retry_after(None) is None
+    assert parse_retry_after("") is None
+    assert parse_retry_after("soon") is None
+
+
+def test_acquire_skips_excluded_keys(tmp_path):
+    pool = APIKeyPool("test", ["k1", "k2"], state_file=tmp_path / "state.json")
+    first = pool.acquire()
+    second = pool.acquire(exclude={first})
+    assert {first, second} == {"k1", "k2"}
+    assert pool.acquire(exclude={"k1", "k2"}) is None
+
+
+def test_acquire_does_not_wait_out_long_cooldowns(tmp_path):
+    pool = APIKeyPool("test", ["k1"], state_file=tmp_path / "state.json")
+    pool.report_failure("k1", status_code=429, retry_after=3000)
+    started = time.time()
+    assert pool.acquire() is None
+    assert pool.acquire(wait=False) is None
+    assert time.time() - started < 5
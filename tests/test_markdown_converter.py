@@ -0,0 +1,43 @@
+"""Tests for HTML to Markdown conversion in processors.markdown_converter."""
+
+from processors.markdown_converter import MarkdownConverter, html_to_markdown
+
+
+def test_headings_paragraphs_and_emphasis():
+    markdown = html_to_markdown("<h2>Title</h2><p>Some <strong>bold</strong> and <em>italic</em> text.</p>")
+    assert markdown == "## Title\n\nSome **bold** and *italic* text.\n"
+
+
+def test_links_are_made_absolute():
+    markdown = html_to_markdown('<p><a href="/about">About us</a> and <a href="#top">top</a></p>',
+                                base_url="https://example.com/post/")
+    assert markdown == "[About us](https://example.com/about) and top\n"
+
+
+def test_lists_and_code_blocks():
+    markdown = html_to_markdown('<ul><li>One</li><li>Two</li></ul>'
+                                '<pre><code class="language-py">print("hi")\n</code></pre>')
+    assert "- One\n- Two" in markdown
+    assert '```python\nprint("hi")\n```' in markdown
+
+
+def test_markdown_syntax_in_text_is_escaped():
+    markdown = html_to_markdown("<p># not a heading with *stars* and [brackets]</p>")
+    assert markdown == "\\# not a heading with \\*stars\\* and \\[brackets\\]\n"
+
+
+def test_image_resolver_localizes_or_drops_images():
+    resolved = {}
+
+    def resolver(url, alt):
+        resolved[url] = alt
+        return None if "pixel" in url else "img-abc.webp"
+
+    converter = MarkdownConverter("https://example.com/post/", resolver)
+    markdown = converter.convert('<p><img src="photo.jpg" alt="A photo"><img src="/pixel.gif"></p>')
+    assert markdown == "![A photo](img-abc.webp)\n"
+    assert resolved == {"https://example.com/post/photo.jpg": "A photo", "https://example.com/pixel.gif": ""}
+
+
+def test_scripts_are_dropped():
+    assert html_to_markdown("<p>Text</p><script>alert(1)</script>") == "Text\n"
@@ -0,0 +1,34 @@
+"""Tests for slugs and post IDs in processors.slugs."""
+
+from processors.slugs import make_slug, normalize_url, post_id, slug_from_url
+
+
+def test_make_slug_keeps_letters_of_any_script():
+    assert make_slug("Hello, World!", romanize=False) == "hello-world"
+    assert make_slug("中文 标题", romanize=False) == "中文-标题"
+    assert make_slug("Café au lait", romanize=True) == "cafe-au-lait"
+
+
+def test_make_slug_cuts_at_a_word_boundary():
+    slug = make_slug("one two three four five six", max_length=15, romanize=False)
+    assert slug == "one-two-three"
+    assert make_slug("!!!", romanize=False) == ""
+
+
+def test_slug_from_url_skips_generic_segments():
+    assert slug_from_url("https://example.com/blog/my-great-post/index.html") == "my-great-post"
+    assert slug_from_url("https://www.example.com/") == "example-com"
+    assert slug_from_url("https://example.com/news/12345", title="Big News") == "big-news"
+
+
+def test_normalize_url_ignores_tracking_and_formatting():
+    assert normalize_url("https://WWW.Example.com/post/?utm_source=x&b=2&a=1") == "https://example.com/post?a=1&b=2"
+    assert normalize_url(None) == ""
+
+
+def test_post_id_is_stable_per_source():
+    pid = post_id(url="https://example.com/post")
+    assert pid == post_id(url="https://www.example.com/post/?utm_campaign=y")
+    assert len(pid) == 8 and pid.isalnum() and pid == pid.lower()
+    assert post_id(video_id="abc123") != post_id(url="abc123")
+    assert post_id(video_id="abc123") == post_id(video_id="abc123")
@@ -0,0 +1,47 @@
+"""Tests for text chunking and FFmpeg concat lists in processors.tts_engine."""
+
+from pathlib import Path
+
+from processors.tts_engine import _concat_entry, split_text_into_chunks
+
+
+def test_chunks_respect_limit_and_keep_every_word():
+    text = " ".join(f"Sentence number {i} is here." for i in range(100))
+    chunks = split_text_into_chunks(text, max_chars=200)
+    assert len(chunks) > 1
+    assert all(len(chunk) <= 200 for chunk in chunks)
+    assert " ".join(chunks).split() == text.split()
+
+
+def test_chunks_break_at_sentence_ends():
+    chunks = split_text_into_chunks("First sentence here. Second sentence here. Third one.", max_chars=45)
+    assert chunks == ["First sentence here. Second sentence here.", "Third one."]
+
+
+def test_oversized_sentence_is_split_on_words():
+    sentence = "word " * 60
+    chunks = split_text_into_chunks(sentence, max_chars=50)
+    assert all(len(chunk) <= 50 for chunk in chunks)
+    assert sum(len(chunk.split()) for chunk in chunks) == 60
+
+
+def test_cjk_text_is_split_without_spaces():
+    text = "这是第一句话。" * 30
+    chunks = split_text_into_chunks(text, max_chars=40)
+    assert all(len(chunk) <= 40 for chunk in chunks)
+    assert "".join(chunks) == text
+
+
+def test_empty_text_has_no_chunks():
+    assert split_text_into_chunks("  \n\n ") == []
+
+
+def test_concat_entry_escapes_single_quotes(tmp_path):
+    audio_file = tmp_path / "it's a chunk.mp3"
+    entry = _concat_entry(audio_file)
+    assert entry == f"file '{audio_file.resolve().parent}/it'\\''s a chunk.mp3'\n"
+
+
+def test_concat_entry_uses_absolute_paths():
+    entry = _concat_entry(Path("relative.mp3"))
+    assert entry.startswith("file '/") and entry.endswith("relative.mp3'\n")
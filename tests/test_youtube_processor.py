@@ -0,0 +1,76 @@
+"""Tests for subtitle parsing and track selection in processors.youtube_processor."""
+
+from processors.youtube_processor import group_segments, parse_vtt_segments, segments_to_text, subtitle_tracks
+
+ROLLING_VTT = """WEBVTT
+Kind: captions
+Language: en
+
+00:00:00.000 --> 00:00:02.500 align:start position:0%
+ 
+hello<00:00:00.500><c> and</c><00:00:01.000><c> welcome</c>
+
+00:00:02.500 --> 00:00:02.510 align:start position:0%
+hello and welcome
+ 
+
+00:00:02.510 --> 00:00:05.000 align:start position:0%
+hello and welcome
+to<00:00:03.000><c> the</c><c> show &amp; more</c>
+"""
+
+SRT = """1
+00:00:01,000 --> 00:00:03,000
+First line.
+
+2
+00:00:03,500 --> 00:00:06,000
+Second line.
+"""
+
+
+def test_rolling_captions_are_not_repeated(tmp_path):
+    vtt_file = tmp_path / "subtitles.en.vtt"
+    vtt_file.write_text(ROLLING_VTT, encoding="utf-8")
+    segments = parse_vtt_segments(vtt_file)
+    assert [segment["text"] for segment in segments] == ["hello and welcome", "to the show & more"]
+    assert segments[0]["start"] == 0.0 and segments[1]["start"] == 2.51
+    assert segments_to_text(segments) == "hello and welcome to the show & more"
+
+
+def test_srt_numbering_is_not_text(tmp_path):
+    srt_file = tmp_path / "subtitles.en.srt"
+    srt_file.write_text(SRT, encoding="utf-8")
+    segments = parse_vtt_segments(srt_file)
+    assert segments == [{"start": 1.0, "end": 3.0, "text": "First line."},
+                        {"start": 3.5, "end": 6.0, "text": "Second line."}]
+
+
+def test_missing_subtitle_file(tmp_path):
+    assert parse_vtt_segments(tmp_path / "missing.vtt") == []
+
+
+def test_group_segments_breaks_at_sentence_ends():
+    segments = [{"start": i * 10.0, "end": i * 10.0 + 10, "text": f"part {i}" + ("." if i == 2 else "")}
+                for i in range(6)]
+    paragraphs = group_segments(segments, min_seconds=20, max_seconds=40)
+    assert [paragraph["start"] for paragraph in paragraphs] == [0.0, 30.0]
+    assert paragraphs[0]["text"] == "part 0 part 1 part 2."
+
+
+def test_subtitle_tracks_prefer_the_spoken_language():
+    info = {
+        "language": "zh",
+        "subtitles": {"live_chat": [], "en": []},
+        "automatic_captions": {"zh-Hans-orig": [], "zh-Hans": [], "en": []},
+    }
+    tracks = subtitle_tracks(info, ["en"])
+    assert tracks == [
+        {"track": "zh-Hans-orig", "language": "zh-Hans", "automatic": True},
+        {"track": "en", "language": "en", "automatic": False},
+        {"track": "en", "language": "en", "automatic": True},
+    ]
+
+
+def test_no_subtitle_tracks():
+    assert subtitle_tracks({"subtitles": {}, "automatic_captions": {}}, ["en"]) == []